@@ -0,0 +1,349 @@
+//! The cgroup v2 hierarchy read from `/sys/fs/cgroup`.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use sysinfo::Pid;
+
+/// A cgroup along with its usage, limits and counters.
+///
+/// Limits and counters of controllers that are not enabled for the cgroup are left empty.
+#[derive(Clone, Debug, Default)]
+pub struct Cgroup {
+    /// The path relative to the root of the hierarchy, e.g. `/system.slice/ssh.service`.
+    pub path: String,
+    /// The number of ancestors.
+    pub depth: usize,
+    /// CPU time consumed, in microseconds.
+    pub cpu_usage: u64,
+    /// The CPU quota and period in microseconds from `cpu.max`, if limited.
+    pub cpu_max: Option<(u64, u64)>,
+    /// Enforcement periods elapsed and those in which the cgroup was throttled.
+    pub periods: u64,
+    pub throttled_periods: u64,
+    /// Time spent throttled, in microseconds.
+    pub throttled_time: u64,
+    pub memory: Option<u64>,
+    pub memory_max: Option<u64>,
+    pub memory_high: Option<u64>,
+    /// Times the cgroup went over its limit and processes killed by the OOM killer.
+    pub oom: u64,
+    pub oom_kill: u64,
+}
+
+impl Cgroup {
+    /// The last component of the path, or `/` for the root.
+    pub fn name(&self) -> &str {
+        match self.path.rsplit_once('/') {
+            Some((_, name)) if !name.is_empty() => name,
+            _ => "/",
+        }
+    }
+}
+
+/// Returns the mount point of the cgroup v2 hierarchy.
+///
+/// Hybrid setups mount it at `/sys/fs/cgroup/unified` next to the v1 controllers.
+pub fn root() -> Option<PathBuf> {
+    ["/sys/fs/cgroup", "/sys/fs/cgroup/unified"]
+        .into_iter()
+        .map(PathBuf::from)
+        .find(|path| path.join("cgroup.controllers").exists())
+}
+
+/// Returns the path of the process in the cgroup v2 hierarchy, e.g. `/system.slice/ssh.service`.
+pub fn process_cgroup(pid: Pid) -> Option<String> {
+    let content = fs::read_to_string(format!("/proc/{pid}/cgroup")).ok()?;
+    // The unified hierarchy is the `0::path` line.
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .map(str::to_string)
+}
+
+/// Whether the cgroup path is the given ancestor or below it.
+pub fn is_within(path: &str, ancestor: &str) -> bool {
+    ancestor == "/"
+        || path
+            .strip_prefix(ancestor)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Reads every cgroup below the root, each one followed by its children.
+pub fn read_tree(root: &Path) -> Vec<Cgroup> {
+    let mut cgroups = Vec::new();
+    read_subtree(root, "/".to_string(), 0, &mut cgroups);
+    cgroups
+}
+
+fn read_subtree(directory: &Path, path: String, depth: usize, cgroups: &mut Vec<Cgroup>) {
+    cgroups.push(read_cgroup(directory, path.clone(), depth));
+
+    let Ok(entries) = fs::read_dir(directory) else {
+        return;
+    };
+    let mut children = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect::<Vec<_>>();
+    children.sort();
+    for child in children {
+        let child_path = match path.as_str() {
+            "/" => format!("/{child}"),
+            _ => format!("{path}/{child}"),
+        };
+        read_subtree(&directory.join(&child), child_path, depth + 1, cgroups);
+    }
+}
+
+fn read_cgroup(directory: &Path, path: String, depth: usize) -> Cgroup {
+    let read = |file: &str| fs::read_to_string(directory.join(file)).ok();
+    let read_keyed = |file: &str| parse_keyed(&read(file).unwrap_or_default());
+    let read_limit = |file: &str| parse_limit(&read(file)?);
+
+    // The root has no `cpu.max` as it cannot be limited.
+    let cpu_max = read("cpu.max").and_then(|content| parse_cpu_max(&content));
+    let cpu_stat = read_keyed("cpu.stat");
+    let memory_events = read_keyed("memory.events");
+    let get =
+        |counters: &HashMap<String, u64>, key: &str| counters.get(key).copied().unwrap_or_default();
+    Cgroup {
+        path,
+        depth,
+        cpu_usage: get(&cpu_stat, "usage_usec"),
+        cpu_max,
+        periods: get(&cpu_stat, "nr_periods"),
+        throttled_periods: get(&cpu_stat, "nr_throttled"),
+        throttled_time: get(&cpu_stat, "throttled_usec"),
+        memory: read_limit("memory.current"),
+        memory_max: read_limit("memory.max"),
+        memory_high: read_limit("memory.high"),
+        oom: get(&memory_events, "oom"),
+        oom_kill: get(&memory_events, "oom_kill"),
+    }
+}
+
+/// Parses a flat keyed file such as `cpu.stat`, which has a `key value` pair per line.
+///
+/// Lines that are not such a pair are skipped.
+fn parse_keyed(content: &str) -> HashMap<String, u64> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(' ')?;
+            Some((key.to_string(), value.trim().parse::<u64>().ok()?))
+        })
+        .collect()
+}
+
+/// Parses a limit such as `memory.max`, which is `max` when unlimited.
+fn parse_limit(content: &str) -> Option<u64> {
+    content.trim().parse().ok()
+}
+
+/// Parses the `quota period` of `cpu.max`, whose quota is `max` when unlimited.
+fn parse_cpu_max(content: &str) -> Option<(u64, u64)> {
+    let (quota, period) = content.trim().split_once(' ')?;
+    Some((quota.parse().ok()?, period.parse().ok()?))
+}
+
+/// Returns the number of processes killed by the OOM killer in each cgroup and its descendants.
+///
+/// Only `memory.events` is read, so this is much cheaper than [`read_tree`].
+pub fn oom_kills(root: &Path) -> HashMap<String, u64> {
+    let mut kills = HashMap::new();
+    collect_oom_kills(root, "/".to_string(), &mut kills);
+    kills
+}
+
+fn collect_oom_kills(directory: &Path, path: String, kills: &mut HashMap<String, u64>) {
+    let count = fs::read_to_string(directory.join("memory.events"))
+        .ok()
+        .and_then(|content| parse_keyed(&content).get("oom_kill").copied());
+    if let Some(count) = count {
+        kills.insert(path.clone(), count);
+    }
+    let Ok(entries) = fs::read_dir(directory) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let child_path = match path.as_str() {
+                "/" => format!("/{name}"),
+                _ => format!("{path}/{name}"),
+            };
+            collect_oom_kills(&entry.path(), child_path, kills);
+        }
+    }
+}
+
+/// Returns the cgroup where the OOM kills counted since the previous counts happened, if
+/// it is not the root.
+///
+/// The counters include the kills in the descendants, so the deepest cgroup whose count grew
+/// is where they happened. Cgroups created since the previous counts had all their kills
+/// since then.
+pub fn oom_kill_cgroup(
+    previous: &HashMap<String, u64>,
+    current: &HashMap<String, u64>,
+) -> Option<String> {
+    current
+        .iter()
+        .filter(|(path, count)| previous.get(*path).copied().unwrap_or_default() < **count)
+        .map(|(path, _)| path)
+        // The root has as many slashes as its children, so it is left out before they are
+        // compared.
+        .filter(|path| *path != "/")
+        .max_by_key(|path| path.matches('/').count())
+        .cloned()
+}
+
+/// Returns the threads of the processes in the given cgroup and its descendants.
+pub fn threads(root: &Path, path: &str) -> HashSet<Pid> {
+    let directory = root.join(path.trim_start_matches('/'));
+    let mut threads = HashSet::new();
+    collect_threads(&directory, &mut threads);
+    threads
+}
+
+fn collect_threads(directory: &Path, threads: &mut HashSet<Pid>) {
+    if let Ok(content) = fs::read_to_string(directory.join("cgroup.threads")) {
+        threads.extend(content.lines().filter_map(|line| line.parse::<Pid>().ok()));
+    }
+    let Ok(entries) = fs::read_dir(directory) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+            collect_threads(&entry.path(), threads);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(counts: &[(&str, u64)]) -> HashMap<String, u64> {
+        counts
+            .iter()
+            .map(|(path, count)| (path.to_string(), *count))
+            .collect()
+    }
+
+    #[test]
+    fn attributes_oom_kills_to_the_deepest_cgroup_that_grew() {
+        let previous = counts(&[
+            ("/", 3),
+            ("/system.slice", 3),
+            ("/system.slice/db.service", 1),
+            ("/system.slice/web.service", 2),
+        ]);
+        let current = counts(&[
+            ("/", 4),
+            ("/system.slice", 4),
+            ("/system.slice/db.service", 2),
+            ("/system.slice/web.service", 2),
+        ]);
+        assert_eq!(
+            oom_kill_cgroup(&previous, &current).as_deref(),
+            Some("/system.slice/db.service")
+        );
+    }
+
+    #[test]
+    fn attributes_oom_kills_to_new_cgroups() {
+        let previous = counts(&[("/", 0), ("/system.slice", 0)]);
+        let current = counts(&[
+            ("/", 1),
+            ("/system.slice", 1),
+            ("/system.slice/docker-1234.scope", 1),
+        ]);
+        assert_eq!(
+            oom_kill_cgroup(&previous, &current).as_deref(),
+            Some("/system.slice/docker-1234.scope")
+        );
+        // A new cgroup without kills is not blamed.
+        let current = counts(&[("/", 1), ("/system.slice", 1), ("/user.slice", 0)]);
+        assert_eq!(
+            oom_kill_cgroup(&previous, &current).as_deref(),
+            Some("/system.slice")
+        );
+    }
+
+    #[test]
+    fn leaves_out_the_root() {
+        let previous = counts(&[("/", 0), ("/system.slice", 0)]);
+        let current = counts(&[("/", 1), ("/system.slice", 0)]);
+        assert_eq!(oom_kill_cgroup(&previous, &current), None);
+        assert_eq!(oom_kill_cgroup(&previous, &previous), None);
+    }
+
+    #[test]
+    fn parses_cpu_max() {
+        assert_eq!(parse_cpu_max("50000 100000\n"), Some((50000, 100000)));
+        assert_eq!(parse_cpu_max("max 100000\n"), None);
+        assert_eq!(parse_cpu_max("50000\n"), None);
+        assert_eq!(parse_cpu_max(""), None);
+    }
+
+    #[test]
+    fn parses_limits() {
+        assert_eq!(parse_limit("536870912\n"), Some(536870912));
+        assert_eq!(parse_limit("max\n"), None);
+        assert_eq!(parse_limit("-1\n"), None);
+    }
+
+    #[test]
+    fn parses_keyed_files() {
+        let cpu_stat = "usage_usec 1500\nuser_usec 1000\nnr_periods 10\nnr_throttled 2\n";
+        let counters = parse_keyed(cpu_stat);
+        assert_eq!(counters.get("usage_usec"), Some(&1500));
+        assert_eq!(counters.get("nr_throttled"), Some(&2));
+
+        let memory_events = "low 0\nhigh 3\nmax 5\noom 1\noom_kill 1\noom_group_kill 0\n";
+        let counters = parse_keyed(memory_events);
+        assert_eq!(counters.get("oom"), Some(&1));
+        assert_eq!(counters.get("oom_kill"), Some(&1));
+
+        // Malformed lines are skipped without dropping the others.
+        let counters = parse_keyed("oom_kill\nhigh three\n\nmax 5 6\noom 2\n");
+        assert_eq!(counters, HashMap::from([("oom".to_string(), 2)]));
+    }
+
+    #[test]
+    fn reads_the_root_without_cpu_max() {
+        let directory = std::env::temp_dir().join(format!("ratatop-cgroup-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(
+            directory.join("cpu.stat"),
+            "usage_usec 1500\nnr_periods 0\n",
+        )
+        .unwrap();
+        fs::write(directory.join("memory.max"), "max\n").unwrap();
+        fs::write(directory.join("memory.events"), "oom 1\noom_kill 1\n").unwrap();
+
+        let cgroup = read_cgroup(&directory, "/".to_string(), 0);
+        fs::remove_dir_all(&directory).unwrap();
+        assert_eq!(cgroup.cpu_usage, 1500);
+        assert_eq!(cgroup.cpu_max, None);
+        assert_eq!(cgroup.memory, None);
+        assert_eq!(cgroup.memory_max, None);
+        assert_eq!(cgroup.oom_kill, 1);
+    }
+
+    #[test]
+    fn finds_descendants() {
+        assert!(is_within("/system.slice", "/system.slice"));
+        assert!(is_within("/system.slice/ssh.service", "/system.slice"));
+        assert!(is_within("/system.slice/ssh.service", "/"));
+        assert!(!is_within("/system.slice-extra", "/system.slice"));
+        assert!(!is_within("/system.slice", "/system.slice/ssh.service"));
+        assert!(!is_within("/user.slice", "/system.slice"));
+    }
+}
@@ -0,0 +1,300 @@
+//! The container, pod or systemd unit of a process, derived from `/proc/<pid>/cgroup` and
+//! `/proc/<pid>/ns`.
+
+use std::{fmt, fs};
+
+use sysinfo::Pid;
+
+/// What a process was found to be running in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Runtime {
+    Docker,
+    Containerd,
+    Podman,
+    CriO,
+    Lxc,
+    /// A Kubernetes pod whose container runtime is not named in the cgroup path.
+    Kubernetes,
+    /// A systemd service, scope or slice.
+    Systemd,
+    /// A PID namespace other than the one of the host.
+    Namespace,
+}
+
+impl fmt::Display for Runtime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Docker => "docker",
+            Self::Containerd => "containerd",
+            Self::Podman => "podman",
+            Self::CriO => "cri-o",
+            Self::Lxc => "lxc",
+            Self::Kubernetes => "k8s",
+            Self::Systemd => "systemd",
+            Self::Namespace => "ns",
+        })
+    }
+}
+
+/// The container of a process.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Container {
+    pub runtime: Runtime,
+    /// The container ID, unit name or namespace inode.
+    pub id: String,
+    /// The UID of the Kubernetes pod the container belongs to.
+    pub pod: Option<String>,
+}
+
+/// The number of characters container IDs are shortened to, as `docker ps` does.
+const SHORT_ID_LEN: usize = 12;
+
+impl fmt::Display for Container {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let id = match self.runtime {
+            Runtime::Systemd | Runtime::Lxc | Runtime::Namespace => self.id.as_str(),
+            _ => &self.id[..self.id.len().min(SHORT_ID_LEN)],
+        };
+        match &self.pod {
+            Some(pod) => write!(f, "{}:pod-{}/{id}", self.runtime, &pod[..pod.len().min(8)]),
+            None => write!(f, "{}:{id}", self.runtime),
+        }
+    }
+}
+
+/// Returns the container of the given process.
+///
+/// Container runtimes are recognized from the cgroup path. Processes in another PID
+/// namespace that none of them claims are identified by the namespace, and the remaining
+/// ones by their systemd unit. Processes in the root cgroup of the host have none.
+pub fn read_container(pid: Pid) -> Option<Container> {
+    let content = fs::read_to_string(format!("/proc/{pid}/cgroup")).ok()?;
+    // Each line is `hierarchy:controllers:path`, the unified hierarchy being `0::path`.
+    let mut paths = content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, ':').skip(1);
+            Some((fields.next()?, fields.next()?))
+        })
+        .collect::<Vec<_>>();
+    // systemd places units in the unified hierarchy, or in its own one on cgroup v1.
+    paths.sort_by_key(|(controllers, _)| !controllers.is_empty() && *controllers != "name=systemd");
+
+    if let Some(container) = paths.iter().find_map(|(_, path)| runtime_container(path)) {
+        return Some(container);
+    }
+    let namespace = pid_namespace(&pid.to_string())
+        .filter(|namespace| host_pid_namespace().is_some_and(|host| host != *namespace));
+    if let Some(namespace) = namespace {
+        return Some(Container {
+            runtime: Runtime::Namespace,
+            id: namespace,
+            pod: None,
+        });
+    }
+    paths.iter().find_map(|(_, path)| systemd_unit(path))
+}
+
+/// Recognizes the container runtimes from the cgroup path, e.g.
+/// `/system.slice/docker-<id>.scope` with the systemd driver or `/docker/<id>` without.
+fn runtime_container(path: &str) -> Option<Container> {
+    let components = path.split('/').collect::<Vec<_>>();
+    let pod = components.iter().find_map(|component| pod_uid(component));
+
+    for (index, component) in components.iter().enumerate().rev() {
+        let unit = component.strip_suffix(".scope").unwrap_or(component);
+        let prefixed = [
+            ("docker-", Runtime::Docker),
+            ("cri-containerd-", Runtime::Containerd),
+            ("crio-", Runtime::CriO),
+            ("libpod-", Runtime::Podman),
+        ]
+        .into_iter()
+        .find_map(|(prefix, runtime)| Some((runtime, unit.strip_prefix(prefix)?)))
+        .filter(|(_, id)| is_container_id(id));
+        if let Some((runtime, id)) = prefixed {
+            return Some(Container {
+                runtime,
+                id: id.to_string(),
+                pod,
+            });
+        }
+        if let Some(name) = component.strip_prefix("lxc.payload.") {
+            return Some(Container {
+                runtime: Runtime::Lxc,
+                id: name.to_string(),
+                pod: None,
+            });
+        }
+
+        // The cgroupfs driver names the cgroup after the ID and its parent after the runtime.
+        let parent = index.checked_sub(1).map(|index| components[index]);
+        if parent == Some("lxc") {
+            return Some(Container {
+                runtime: Runtime::Lxc,
+                id: component.to_string(),
+                pod: None,
+            });
+        }
+        if !is_container_id(component) {
+            continue;
+        }
+        let runtime = match parent {
+            Some("docker") => Runtime::Docker,
+            Some("libpod_parent") => Runtime::Podman,
+            _ if pod.is_some() => Runtime::Kubernetes,
+            _ if components.contains(&"containerd") => Runtime::Containerd,
+            _ => continue,
+        };
+        return Some(Container {
+            runtime,
+            id: component.to_string(),
+            pod,
+        });
+    }
+    None
+}
+
+/// Returns the UID of the pod in a component such as `pod<uid>` with the cgroupfs driver or
+/// `kubepods-burstable-pod<uid>.slice` with the systemd one.
+fn pod_uid(component: &str) -> Option<String> {
+    let name = component.strip_suffix(".slice").unwrap_or(component);
+    let (_, uid) = name.rsplit_once("pod")?;
+    // The systemd driver escapes the dashes of the UID.
+    let uid = uid.replace('_', "-");
+    (uid.len() == 36 && uid.chars().all(|c| c.is_ascii_hexdigit() || c == '-')).then_some(uid)
+}
+
+/// Whether the text looks like the 64 hexadecimal digits of a container ID.
+fn is_container_id(text: &str) -> bool {
+    text.len() == 64 && text.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Returns the innermost systemd service or scope of the cgroup path, or its slice.
+fn systemd_unit(path: &str) -> Option<Container> {
+    let mut units = path
+        .split('/')
+        .filter(|component| {
+            component.ends_with(".service")
+                || component.ends_with(".scope")
+                || component.ends_with(".slice")
+        })
+        .collect::<Vec<_>>();
+    // Slices only group units, so a unit below them is more specific.
+    let unit = match units.iter().rposition(|unit| !unit.ends_with(".slice")) {
+        Some(index) => units.swap_remove(index),
+        None => units.pop()?,
+    };
+    Some(Container {
+        runtime: Runtime::Systemd,
+        id: unit.to_string(),
+        pod: None,
+    })
+}
+
+/// Returns the inode of the PID namespace of the given process, e.g. `4026531836`.
+fn pid_namespace(pid: &str) -> Option<String> {
+    let link = fs::read_link(format!("/proc/{pid}/ns/pid")).ok()?;
+    let link = link.to_string_lossy();
+    let inode = link.strip_prefix("pid:[")?.strip_suffix(']')?;
+    Some(inode.to_string())
+}
+
+/// Returns the PID namespace of the host, or of ratatop when the one of init is not readable.
+fn host_pid_namespace() -> Option<String> {
+    pid_namespace("1").or_else(|| pid_namespace("self"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "4a6d4f3b8c1e9f2a7b5d0c6e8f1a3b9c2d7e5f0a4b8c6d1e3f9a2b7c5d0e8f4a";
+    const POD: &str = "5d3c8f2e-1a4b-4c6d-9e8f-0a1b2c3d4e5f";
+
+    fn container(runtime: Runtime, id: &str, pod: Option<&str>) -> Option<Container> {
+        Some(Container {
+            runtime,
+            id: id.to_string(),
+            pod: pod.map(str::to_string),
+        })
+    }
+
+    #[test]
+    fn recognizes_the_systemd_driver() {
+        assert_eq!(
+            runtime_container(&format!("/system.slice/docker-{ID}.scope")),
+            container(Runtime::Docker, ID, None)
+        );
+        assert_eq!(
+            runtime_container(&format!("/machine.slice/libpod-{ID}.scope/container")),
+            container(Runtime::Podman, ID, None)
+        );
+        let pod = POD.replace('-', "_");
+        assert_eq!(
+            runtime_container(&format!(
+                "/kubepods.slice/kubepods-burstable.slice/kubepods-burstable-pod{pod}.slice/\
+                 cri-containerd-{ID}.scope"
+            )),
+            container(Runtime::Containerd, ID, Some(POD))
+        );
+        assert_eq!(
+            runtime_container(&format!(
+                "/kubepods.slice/kubepods-pod{pod}.slice/crio-{ID}.scope"
+            )),
+            container(Runtime::CriO, ID, Some(POD))
+        );
+    }
+
+    #[test]
+    fn recognizes_the_cgroupfs_driver() {
+        assert_eq!(
+            runtime_container(&format!("/docker/{ID}")),
+            container(Runtime::Docker, ID, None)
+        );
+        assert_eq!(
+            runtime_container(&format!("/kubepods/besteffort/pod{POD}/{ID}")),
+            container(Runtime::Kubernetes, ID, Some(POD))
+        );
+        assert_eq!(
+            runtime_container("/lxc/web"),
+            container(Runtime::Lxc, "web", None)
+        );
+        assert_eq!(
+            runtime_container("/lxc.payload.web/system.slice/nginx.service"),
+            container(Runtime::Lxc, "web", None)
+        );
+    }
+
+    #[test]
+    fn ignores_paths_without_containers() {
+        assert_eq!(runtime_container("/"), None);
+        assert_eq!(
+            runtime_container("/user.slice/user-1000.slice/session-2.scope"),
+            None
+        );
+        // Not a full container ID.
+        assert_eq!(
+            runtime_container("/system.slice/docker-4a6d4f3b.scope"),
+            None
+        );
+        assert_eq!(runtime_container(&format!("/unknown/{ID}")), None);
+    }
+
+    #[test]
+    fn finds_the_innermost_systemd_unit() {
+        assert_eq!(
+            systemd_unit("/system.slice/ssh.service"),
+            container(Runtime::Systemd, "ssh.service", None)
+        );
+        assert_eq!(
+            systemd_unit("/user.slice/user-1000.slice/user@1000.service/app.slice/vte.scope"),
+            container(Runtime::Systemd, "vte.scope", None)
+        );
+        assert_eq!(
+            systemd_unit("/user.slice/user-1000.slice"),
+            container(Runtime::Systemd, "user-1000.slice", None)
+        );
+        assert_eq!(systemd_unit("/"), None);
+    }
+}
@@ -0,0 +1,347 @@
+//! Directory sizes scanned in the background, like `du -x`.
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+};
+
+/// A file or directory along with the size of everything below it.
+#[derive(Clone, Debug, Default)]
+pub struct Node {
+    pub name: String,
+    /// The space used on disk, in bytes.
+    pub size: u64,
+    pub is_dir: bool,
+    /// The largest subdirectories of a directory, the largest first.
+    ///
+    /// Files are only counted in [`Node::other_entries`] so that the memory used does not
+    /// grow with the number of files.
+    pub children: Vec<Node>,
+    /// The number of files and smaller subdirectories that are not kept in the children.
+    pub other_entries: u64,
+    /// The space used by the entries that are not kept.
+    pub other_size: u64,
+}
+
+/// Number of the largest subdirectories kept for each directory.
+const CHILDREN_LEN: usize = 100;
+
+impl Node {
+    /// Adds an entry to the directory, only keeping the largest subdirectories.
+    fn push_child(&mut self, child: Node) {
+        self.size += child.size;
+        if !child.is_dir {
+            self.other_entries += 1;
+            self.other_size += child.size;
+            return;
+        }
+        self.children.push(child);
+        // The smaller ones are dropped in batches to avoid sorting on every entry.
+        if self.children.len() >= 2 * CHILDREN_LEN {
+            self.sort_children();
+        }
+    }
+
+    /// Sorts the subdirectories, the largest first, and drops the smaller ones.
+    fn sort_children(&mut self) {
+        self.children
+            .sort_by_key(|child| std::cmp::Reverse(child.size));
+        for child in self.children.drain(CHILDREN_LEN.min(self.children.len())..) {
+            self.other_entries += 1;
+            self.other_size += child.size;
+        }
+    }
+
+    /// Returns the percentage of the size of the directory used by the given size.
+    pub fn share(&self, size: u64) -> f64 {
+        if self.size == 0 {
+            return 0.0;
+        }
+        size as f64 / self.size as f64 * 100.0
+    }
+}
+
+/// A message sent by the scanning thread.
+#[derive(Debug)]
+enum Message {
+    Progress {
+        files: u64,
+        size: u64,
+    },
+    /// The root, before its entries are scanned.
+    Root(Node),
+    /// An entry of the root, once fully scanned.
+    Entry(Node),
+    Done,
+}
+
+/// A directory scan running in the background.
+///
+/// The scan stops once this is dropped.
+#[derive(Debug)]
+pub struct Scan {
+    pub root: PathBuf,
+    /// Files scanned so far.
+    pub files: u64,
+    /// Bytes scanned so far.
+    pub size: u64,
+    /// The scanned tree, with the entries of the root added as they are scanned.
+    pub tree: Option<Node>,
+    /// Whether the whole tree has been scanned.
+    pub done: bool,
+    receiver: Receiver<Message>,
+}
+
+/// How many files are scanned between two progress messages.
+const PROGRESS_INTERVAL: u64 = 1000;
+
+/// How deep directories are scanned below the root.
+///
+/// Deeper directories only count for their own size, which guards the recursion against
+/// pathologically nested trees.
+const MAX_DEPTH: usize = 256;
+
+impl Scan {
+    /// Starts scanning the given directory on a background thread.
+    pub fn start(root: PathBuf) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let scanned_root = root.clone();
+        thread::spawn(move || {
+            let mut scanner = Scanner {
+                sender,
+                device: device(&scanned_root),
+                files: 0,
+                size: 0,
+                seen: HashSet::new(),
+            };
+            if scanner.scan_root(&scanned_root).is_some() {
+                let _ = scanner.sender.send(Message::Done);
+            }
+        });
+        Self {
+            root,
+            files: 0,
+            size: 0,
+            tree: None,
+            done: false,
+            receiver,
+        }
+    }
+
+    /// Receives the progress and the scanned entries without blocking.
+    pub fn poll(&mut self) {
+        let mut received_entries = false;
+        while let Ok(message) = self.receiver.try_recv() {
+            match message {
+                Message::Progress { files, size } => {
+                    self.files = files;
+                    self.size = size;
+                }
+                Message::Root(root) => self.tree = Some(root),
+                Message::Entry(entry) => {
+                    if let Some(tree) = &mut self.tree {
+                        tree.push_child(entry);
+                        received_entries = true;
+                    }
+                }
+                Message::Done => {
+                    self.done = true;
+                    if let Some(tree) = &self.tree {
+                        self.size = tree.size;
+                    }
+                }
+            }
+        }
+        if let Some(tree) = self.tree.as_mut().filter(|_| received_entries) {
+            tree.sort_children();
+        }
+    }
+}
+
+/// The state of the scanning thread.
+struct Scanner {
+    sender: Sender<Message>,
+    /// The device of the root, so that other filesystems are not crossed.
+    device: Option<u64>,
+    files: u64,
+    size: u64,
+    /// Files with several hard links that were already counted.
+    seen: HashSet<(u64, u64)>,
+}
+
+impl Scanner {
+    /// Scans the root and sends each of its entries once scanned, or returns `None` once the
+    /// scan is no longer wanted.
+    fn scan_root(&mut self, root: &Path) -> Option<()> {
+        let metadata = fs::symlink_metadata(root).ok();
+        let node = Node {
+            name: root.display().to_string(),
+            size: metadata.map_or(0, |metadata| self.disk_usage(&metadata)),
+            is_dir: true,
+            ..Node::default()
+        };
+        self.sender.send(Message::Root(node)).ok()?;
+        let Ok(entries) = fs::read_dir(root) else {
+            return Some(());
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let child = self.scan(&entry.path(), name, 1)?;
+            self.sender.send(Message::Entry(child)).ok()?;
+        }
+        Some(())
+    }
+
+    /// Scans the given path at the given depth below the root, or returns `None` once the
+    /// scan is no longer wanted.
+    fn scan(&mut self, path: &Path, name: String, depth: usize) -> Option<Node> {
+        let Ok(metadata) = fs::symlink_metadata(path) else {
+            return Some(Node {
+                name,
+                ..Node::default()
+            });
+        };
+        let mut node = Node {
+            name,
+            size: self.disk_usage(&metadata),
+            is_dir: metadata.is_dir(),
+            ..Node::default()
+        };
+
+        self.files += 1;
+        self.size += node.size;
+        if self.files.is_multiple_of(PROGRESS_INTERVAL) {
+            let progress = Message::Progress {
+                files: self.files,
+                size: self.size,
+            };
+            // The receiver is gone, nobody is waiting for the result anymore.
+            self.sender.send(progress).ok()?;
+        }
+
+        if !node.is_dir
+            || depth >= MAX_DEPTH
+            || (self.device.is_some() && device(path) != self.device)
+        {
+            return Some(node);
+        }
+        let Ok(entries) = fs::read_dir(path) else {
+            return Some(node);
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let child = self.scan(&entry.path(), name, depth + 1)?;
+            node.push_child(child);
+        }
+        node.sort_children();
+        Some(node)
+    }
+
+    /// Returns the space used on disk by the file, counting hard links once.
+    #[cfg(unix)]
+    fn disk_usage(&mut self, metadata: &fs::Metadata) -> u64 {
+        use std::os::unix::fs::MetadataExt;
+
+        if metadata.nlink() > 1 && !self.seen.insert((metadata.dev(), metadata.ino())) {
+            return 0;
+        }
+        metadata.blocks() * 512
+    }
+
+    #[cfg(not(unix))]
+    fn disk_usage(&mut self, metadata: &fs::Metadata) -> u64 {
+        metadata.len()
+    }
+}
+
+/// Returns the device of the filesystem the path is on.
+#[cfg(unix)]
+fn device(path: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+
+    fs::symlink_metadata(path)
+        .ok()
+        .map(|metadata| metadata.dev())
+}
+
+#[cfg(not(unix))]
+fn device(_path: &Path) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(name: &str, size: u64, is_dir: bool) -> Node {
+        Node {
+            name: name.to_string(),
+            size,
+            is_dir,
+            ..Node::default()
+        }
+    }
+
+    #[test]
+    fn counts_files_outside_the_children() {
+        let mut directory = node("home", 4096, true);
+        let mut documents = node("documents", 4096, true);
+        documents.push_child(node("report.pdf", 8192, false));
+        directory.push_child(documents);
+        directory.push_child(node("notes.txt", 4096, false));
+        directory.push_child(node("music", 65536, true));
+        directory.sort_children();
+
+        assert_eq!(directory.size, 4096 + 4096 + 8192 + 4096 + 65536);
+        let names = directory
+            .children
+            .iter()
+            .map(|child| child.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["music", "documents"]);
+        assert_eq!(directory.children[1].size, 4096 + 8192);
+        assert_eq!(directory.other_entries, 1);
+        assert_eq!(directory.other_size, 4096);
+    }
+
+    #[test]
+    fn keeps_the_largest_children() {
+        let mut directory = node("var", 0, true);
+        let len = 2 * CHILDREN_LEN as u64 + 10;
+        for size in 1..=len {
+            directory.push_child(node(&size.to_string(), size, true));
+        }
+        // The smaller ones were dropped once twice as many were pushed.
+        assert!(directory.children.len() < 2 * CHILDREN_LEN);
+        directory.sort_children();
+
+        assert_eq!(directory.children.len(), CHILDREN_LEN);
+        let sizes = directory
+            .children
+            .iter()
+            .map(|child| child.size)
+            .collect::<Vec<_>>();
+        let largest = (len - CHILDREN_LEN as u64 + 1..=len)
+            .rev()
+            .collect::<Vec<_>>();
+        assert_eq!(sizes, largest);
+
+        let dropped = len - CHILDREN_LEN as u64;
+        assert_eq!(directory.other_entries, dropped);
+        assert_eq!(directory.other_size, dropped * (dropped + 1) / 2);
+        assert_eq!(directory.size, len * (len + 1) / 2);
+    }
+
+    #[test]
+    fn computes_shares() {
+        let directory = node("srv", 400, true);
+        assert_eq!(directory.share(100), 25.0);
+        assert_eq!(directory.share(400), 100.0);
+        assert_eq!(directory.share(0), 0.0);
+        // An empty directory is not divided by zero.
+        assert_eq!(node("empty", 0, true).share(0), 0.0);
+    }
+}
@@ -0,0 +1,55 @@
+//! Network interface details read from `/sys/class/net`.
+
+use std::{fs, path::Path};
+
+/// The link and cumulative counters of a network interface.
+#[derive(Clone, Debug, Default)]
+pub struct Link {
+    /// The operational state, e.g. `up` or `down`.
+    pub operstate: String,
+    pub mac_address: String,
+    pub mtu: u64,
+    /// The link speed in Mbit/s, if known.
+    pub speed: Option<u64>,
+    pub received_bytes: u64,
+    pub transmitted_bytes: u64,
+    /// Receive and transmit errors.
+    pub errors: u64,
+    /// Received and transmitted packets dropped by the interface.
+    pub dropped: u64,
+    pub collisions: u64,
+    pub loopback: bool,
+    /// Whether the interface is not backed by a device, e.g. `veth` or `bridge`.
+    pub virtual_device: bool,
+}
+
+/// The hardware type of loopback interfaces (`ARPHRD_LOOPBACK`).
+const LOOPBACK_TYPE: &str = "772";
+
+/// Reads the link of the given interface.
+///
+/// Missing attributes are left empty, e.g. the speed of virtual interfaces.
+pub fn read_interface(name: &str) -> Link {
+    let path = Path::new("/sys/class/net").join(name);
+    let read = |attribute: &str| {
+        fs::read_to_string(path.join(attribute))
+            .map(|value| value.trim().to_string())
+            .unwrap_or_default()
+    };
+    let read_number = |attribute: &str| read(attribute).parse::<u64>().unwrap_or_default();
+    Link {
+        operstate: read("operstate"),
+        mac_address: read("address"),
+        mtu: read_number("mtu"),
+        // Unknown speeds are reported as -1 or fail to read.
+        speed: read("speed").parse().ok().filter(|&speed| speed > 0),
+        received_bytes: read_number("statistics/rx_bytes"),
+        transmitted_bytes: read_number("statistics/tx_bytes"),
+        errors: read_number("statistics/rx_errors") + read_number("statistics/tx_errors"),
+        dropped: read_number("statistics/rx_dropped") + read_number("statistics/tx_dropped"),
+        collisions: read_number("statistics/collisions"),
+        loopback: read("type") == LOOPBACK_TYPE,
+        virtual_device: fs::canonicalize(&path)
+            .is_ok_and(|path| path.starts_with("/sys/devices/virtual")),
+    }
+}
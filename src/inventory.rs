@@ -0,0 +1,556 @@
+//! A summary of the hardware and system, read from `/proc` and `/sys`.
+
+use std::{collections::HashSet, fmt::Write, fs, path::Path};
+
+use crate::{format_bytes, interfaces};
+
+/// The hardware and system of the machine.
+#[derive(Clone, Debug, Default)]
+pub struct Inventory {
+    pub host_name: String,
+    /// The `PRETTY_NAME` of `/etc/os-release`.
+    pub os_release: String,
+    pub kernel: String,
+    pub command_line: String,
+    pub cpu: Cpu,
+    /// Total memory and swap, in bytes.
+    pub memory: u64,
+    pub swap: u64,
+    pub block_devices: Vec<BlockDevice>,
+    pub interfaces: Vec<Interface>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Cpu {
+    pub model: String,
+    pub architecture: String,
+    pub sockets: usize,
+    pub cores: usize,
+    pub threads: usize,
+    /// The caches of the first CPU.
+    pub caches: Vec<Cache>,
+    /// The hardware virtualization extension, e.g. `VT-x`.
+    pub virtualization: Option<String>,
+    /// The hypervisor the machine runs on, if it is virtual.
+    pub hypervisor: Option<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Cache {
+    /// The name, e.g. `L1d` or `L3`.
+    pub name: String,
+    /// The size in bytes.
+    pub size: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BlockDevice {
+    pub name: String,
+    /// The size in bytes.
+    pub size: u64,
+    pub model: String,
+    pub rotational: bool,
+    pub removable: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Interface {
+    pub name: String,
+    pub mac_address: String,
+    /// The link speed in Mbit/s, if known.
+    pub speed: Option<u64>,
+    pub mtu: u64,
+    pub operstate: String,
+}
+
+/// The size of the sectors `/sys/block/<device>/size` is counted in.
+const SECTOR_SIZE: u64 = 512;
+
+impl Inventory {
+    /// Reads the inventory of the machine.
+    ///
+    /// What cannot be read is left empty.
+    pub fn read() -> Self {
+        let read = |path: &str| {
+            fs::read_to_string(path)
+                .map(|content| content.trim().to_string())
+                .unwrap_or_default()
+        };
+        let meminfo = read("/proc/meminfo");
+        // Values of `/proc/meminfo` are in KiB.
+        let meminfo_bytes = |key: &str| {
+            meminfo
+                .lines()
+                .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+                .and_then(|value| value.trim().trim_end_matches(" kB").parse::<u64>().ok())
+                .map_or(0, |value| value * 1024)
+        };
+        let os_release = read("/etc/os-release")
+            .lines()
+            .find_map(|line| line.strip_prefix("PRETTY_NAME="))
+            .map(|name| name.trim_matches('"').to_string())
+            .unwrap_or_default();
+        Self {
+            host_name: read("/proc/sys/kernel/hostname"),
+            os_release,
+            kernel: read("/proc/sys/kernel/osrelease"),
+            command_line: read("/proc/cmdline"),
+            cpu: read_cpu(),
+            memory: meminfo_bytes("MemTotal"),
+            swap: meminfo_bytes("SwapTotal"),
+            block_devices: read_block_devices(),
+            interfaces: read_interfaces(),
+        }
+    }
+
+    /// Returns the inventory as titled sections of labelled values.
+    pub fn sections(&self) -> Vec<(&'static str, Vec<(String, String)>)> {
+        let entry = |label: &str, value: String| (label.to_string(), value);
+        let cpu = &self.cpu;
+        let mut cpu_entries = vec![
+            entry("Model", cpu.model.clone()),
+            entry("Architecture", cpu.architecture.clone()),
+            entry("Sockets", cpu.sockets.to_string()),
+            entry("Cores", cpu.cores.to_string()),
+            entry("Threads", cpu.threads.to_string()),
+        ];
+        cpu_entries.extend(
+            cpu.caches
+                .iter()
+                .map(|cache| entry(&format!("{} cache", cache.name), format_bytes(cache.size))),
+        );
+        cpu_entries.push(entry(
+            "Virtualization",
+            cpu.virtualization
+                .clone()
+                .unwrap_or_else(|| "none".to_string()),
+        ));
+        cpu_entries.push(entry(
+            "Hypervisor",
+            cpu.hypervisor.clone().unwrap_or_else(|| "none".to_string()),
+        ));
+
+        vec![
+            (
+                "System",
+                vec![
+                    entry("Host name", self.host_name.clone()),
+                    entry("OS", self.os_release.clone()),
+                    entry("Kernel", self.kernel.clone()),
+                    entry("Command line", self.command_line.clone()),
+                ],
+            ),
+            ("CPU", cpu_entries),
+            (
+                "Memory",
+                vec![
+                    entry("Total", format_bytes(self.memory)),
+                    entry("Swap", format_bytes(self.swap)),
+                ],
+            ),
+            (
+                "Block devices",
+                self.block_devices
+                    .iter()
+                    .map(|device| {
+                        let mut kind = vec![if device.rotational { "HDD" } else { "SSD" }];
+                        if device.removable {
+                            kind.push("removable");
+                        }
+                        let mut value = format_bytes(device.size);
+                        // Virtual devices have no model.
+                        if !device.model.is_empty() {
+                            let _ = write!(value, " {}", device.model);
+                        }
+                        let _ = write!(value, " ({})", kind.join(", "));
+                        entry(&device.name, value)
+                    })
+                    .collect(),
+            ),
+            (
+                "Network interfaces",
+                self.interfaces
+                    .iter()
+                    .map(|interface| {
+                        let speed = interface
+                            .speed
+                            .map(|speed| format!(" {speed} Mbit/s"))
+                            .unwrap_or_default();
+                        let value = format!(
+                            "{} {}{speed} MTU {}",
+                            interface.operstate, interface.mac_address, interface.mtu
+                        );
+                        entry(&interface.name, value)
+                    })
+                    .collect(),
+            ),
+        ]
+    }
+
+    /// Formats the inventory as indented plain text.
+    pub fn to_text(&self) -> String {
+        let sections = self.sections();
+        let width = sections
+            .iter()
+            .flat_map(|(_, entries)| entries.iter().map(|(label, _)| label.len()))
+            .max()
+            .unwrap_or_default();
+        let mut text = String::new();
+        for (title, entries) in sections {
+            let _ = writeln!(text, "{title}");
+            for (label, value) in entries {
+                let _ = writeln!(text, "  {label:width$}  {value}");
+            }
+        }
+        text
+    }
+
+    /// Formats the inventory as JSON, with sizes in bytes.
+    pub fn to_json(&self) -> String {
+        let cpu = &self.cpu;
+        let caches = cpu
+            .caches
+            .iter()
+            .map(|cache| {
+                format!(
+                    r#"{{"name":{},"size":{}}}"#,
+                    json_string(&cache.name),
+                    cache.size
+                )
+            })
+            .collect::<Vec<_>>();
+        let block_devices = self
+            .block_devices
+            .iter()
+            .map(|device| {
+                format!(
+                    r#"{{"name":{},"size":{},"model":{},"rotational":{},"removable":{}}}"#,
+                    json_string(&device.name),
+                    device.size,
+                    json_string(&device.model),
+                    device.rotational,
+                    device.removable
+                )
+            })
+            .collect::<Vec<_>>();
+        let interfaces = self
+            .interfaces
+            .iter()
+            .map(|interface| {
+                format!(
+                    r#"{{"name":{},"mac_address":{},"speed":{},"mtu":{},"operstate":{}}}"#,
+                    json_string(&interface.name),
+                    json_string(&interface.mac_address),
+                    json_option(interface.speed.map(|speed| speed.to_string())),
+                    interface.mtu,
+                    json_string(&interface.operstate)
+                )
+            })
+            .collect::<Vec<_>>();
+        format!(
+            concat!(
+                r#"{{"host_name":{},"os_release":{},"kernel":{},"command_line":{},"#,
+                r#""cpu":{{"model":{},"architecture":{},"sockets":{},"cores":{},"threads":{},"#,
+                r#""caches":[{}],"virtualization":{},"hypervisor":{}}},"#,
+                r#""memory":{},"swap":{},"block_devices":[{}],"interfaces":[{}]}}"#
+            ),
+            json_string(&self.host_name),
+            json_string(&self.os_release),
+            json_string(&self.kernel),
+            json_string(&self.command_line),
+            json_string(&cpu.model),
+            json_string(&cpu.architecture),
+            cpu.sockets,
+            cpu.cores,
+            cpu.threads,
+            caches.join(","),
+            json_option(cpu.virtualization.as_deref().map(json_string)),
+            json_option(cpu.hypervisor.as_deref().map(json_string)),
+            self.memory,
+            self.swap,
+            block_devices.join(","),
+            interfaces.join(","),
+        )
+    }
+}
+
+fn read_cpu() -> Cpu {
+    let cpuinfo = fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+    let mut cpu = Cpu {
+        architecture: machine(),
+        ..Cpu::default()
+    };
+    let mut sockets = HashSet::new();
+    let mut cores = HashSet::new();
+    let mut socket = "";
+    let mut flags = HashSet::new();
+    let mut part = None;
+    // Each logical CPU is a block of `key : value` lines.
+    for line in cpuinfo.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "processor" => cpu.threads += 1,
+            "model name" if cpu.model.is_empty() => cpu.model = value.to_string(),
+            // ARM CPUs have no model name, only part numbers.
+            "CPU part" if part.is_none() => part = Some(value),
+            "physical id" => {
+                socket = value;
+                sockets.insert(value);
+            }
+            "core id" => {
+                cores.insert((socket, value));
+            }
+            "flags" if flags.is_empty() => flags.extend(value.split_whitespace()),
+            _ => {}
+        }
+    }
+    if cpu.model.is_empty() {
+        cpu.model = arm_model(part);
+    }
+    // Some architectures do not report the topology.
+    cpu.sockets = sockets.len().max(1);
+    cpu.cores = if cores.is_empty() {
+        cpu.threads
+    } else {
+        cores.len()
+    };
+
+    cpu.virtualization = if flags.contains("vmx") {
+        Some("VT-x".to_string())
+    } else if flags.contains("svm") {
+        Some("AMD-V".to_string())
+    } else {
+        None
+    };
+    cpu.hypervisor = flags.contains("hypervisor").then(|| {
+        ["/sys/hypervisor/type", "/sys/class/dmi/id/sys_vendor"]
+            .into_iter()
+            .find_map(|path| {
+                let vendor = fs::read_to_string(path).ok()?;
+                Some(vendor.trim().to_string()).filter(|vendor| !vendor.is_empty())
+            })
+            .unwrap_or_else(|| "unknown".to_string())
+    });
+    cpu.caches = read_caches(Path::new("/sys/devices/system/cpu/cpu0/cache"));
+    cpu
+}
+
+/// Returns the hardware the kernel runs on, e.g. `x86_64` or `aarch64`.
+///
+/// This is not necessarily the architecture ratatop was built for, e.g. for a 32-bit build.
+#[cfg(unix)]
+fn machine() -> String {
+    // SAFETY: `name` is a plain C struct that `uname` fills in with null-terminated strings.
+    let mut name = unsafe { std::mem::zeroed::<libc::utsname>() };
+    if unsafe { libc::uname(&mut name) } != 0 {
+        return std::env::consts::ARCH.to_string();
+    }
+    let machine = unsafe { std::ffi::CStr::from_ptr(name.machine.as_ptr()) };
+    machine.to_string_lossy().to_string()
+}
+
+#[cfg(not(unix))]
+fn machine() -> String {
+    std::env::consts::ARCH.to_string()
+}
+
+/// Returns the model of the board from the device tree, or the part number of the CPU.
+fn arm_model(part: Option<&str>) -> String {
+    let board = fs::read_to_string("/sys/firmware/devicetree/base/model").unwrap_or_default();
+    // The device tree strings are terminated with a null character.
+    let board = board.trim_end_matches('\0').trim();
+    match part {
+        _ if !board.is_empty() => board.to_string(),
+        Some(part) => format!("CPU part {part}"),
+        None => String::new(),
+    }
+}
+
+fn read_caches(directory: &Path) -> Vec<Cache> {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return Vec::new();
+    };
+    let mut caches = entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("index"))
+        .filter_map(|entry| {
+            let read = |file: &str| fs::read_to_string(entry.path().join(file)).ok();
+            let level = read("level")?;
+            let suffix = match read("type")?.trim() {
+                "Data" => "d",
+                "Instruction" => "i",
+                _ => "",
+            };
+            // Sizes are given with a unit, e.g. `48K`.
+            let size = read("size")?;
+            let size = size.trim();
+            let (number, multiplier) = match size.char_indices().last()? {
+                (index, 'K') => (&size[..index], 1 << 10),
+                (index, 'M') => (&size[..index], 1 << 20),
+                (index, 'G') => (&size[..index], 1 << 30),
+                _ => (size, 1),
+            };
+            Some(Cache {
+                name: format!("L{}{suffix}", level.trim()),
+                size: number.parse::<u64>().ok()? * multiplier,
+            })
+        })
+        .collect::<Vec<_>>();
+    caches.sort_by(|a, b| a.name.cmp(&b.name));
+    caches
+}
+
+fn read_block_devices() -> Vec<BlockDevice> {
+    let Ok(entries) = fs::read_dir("/sys/block") else {
+        return Vec::new();
+    };
+    let mut devices = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let read = |file: &str| {
+                fs::read_to_string(path.join(file))
+                    .map(|content| content.trim().to_string())
+                    .unwrap_or_default()
+            };
+            let size = read("size").parse::<u64>().ok()? * SECTOR_SIZE;
+            // Unused loop and RAM devices are empty.
+            (size > 0).then(|| BlockDevice {
+                name: entry.file_name().to_string_lossy().to_string(),
+                size,
+                model: read("device/model"),
+                rotational: read("queue/rotational") == "1",
+                removable: read("removable") == "1",
+            })
+        })
+        .collect::<Vec<_>>();
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    devices
+}
+
+fn read_interfaces() -> Vec<Interface> {
+    let Ok(entries) = fs::read_dir("/sys/class/net") else {
+        return Vec::new();
+    };
+    let mut interfaces = entries
+        .flatten()
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let link = interfaces::read_interface(&name);
+            Interface {
+                name,
+                mac_address: link.mac_address,
+                speed: link.speed,
+                mtu: link.mtu,
+                operstate: link.operstate,
+            }
+        })
+        .collect::<Vec<_>>();
+    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+    interfaces
+}
+
+/// Quotes and escapes the text as a JSON string.
+fn json_string(text: &str) -> String {
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Returns the JSON value, or `null` if there is none.
+fn json_option(value: Option<String>) -> String {
+    value.unwrap_or_else(|| "null".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_json_strings() {
+        assert_eq!(json_string("eth0"), r#""eth0""#);
+        assert_eq!(json_string(r#"say "hi""#), r#""say \"hi\"""#);
+        assert_eq!(json_string(r"C:\dir\"), r#""C:\\dir\\""#);
+        assert_eq!(json_string("a\nb\tc"), r#""a\nb\tc""#);
+        assert_eq!(json_string("\r\u{1}\u{7f}"), r#""\u000d\u0001\u007f""#);
+        assert_eq!(json_string("Ünïcødé ✓"), "\"Ünïcødé ✓\"");
+    }
+
+    #[test]
+    fn exports_nested_json() {
+        let inventory = Inventory {
+            host_name: "vm".to_string(),
+            os_release: "Debian \"bookworm\"".to_string(),
+            kernel: "6.1.0".to_string(),
+            command_line: r"root=\dev".to_string(),
+            cpu: Cpu {
+                model: "Xeon".to_string(),
+                architecture: "x86_64".to_string(),
+                sockets: 1,
+                cores: 2,
+                threads: 4,
+                caches: vec![Cache {
+                    name: "L1d".to_string(),
+                    size: 32768,
+                }],
+                virtualization: Some("VT-x".to_string()),
+                hypervisor: None,
+            },
+            memory: 1024,
+            swap: 0,
+            block_devices: vec![BlockDevice {
+                name: "vda".to_string(),
+                size: 512,
+                model: String::new(),
+                rotational: true,
+                removable: false,
+            }],
+            interfaces: vec![Interface {
+                name: "eth0".to_string(),
+                mac_address: "52:54:00:12:34:56".to_string(),
+                speed: None,
+                mtu: 1500,
+                operstate: "up".to_string(),
+            }],
+        };
+        assert_eq!(
+            inventory.to_json(),
+            concat!(
+                r#"{"host_name":"vm","os_release":"Debian \"bookworm\"","kernel":"6.1.0","#,
+                r#""command_line":"root=\\dev","#,
+                r#""cpu":{"model":"Xeon","architecture":"x86_64","sockets":1,"cores":2,"#,
+                r#""threads":4,"caches":[{"name":"L1d","size":32768}],"#,
+                r#""virtualization":"VT-x","hypervisor":null},"#,
+                r#""memory":1024,"swap":0,"#,
+                r#""block_devices":[{"name":"vda","size":512,"model":"","rotational":true,"#,
+                r#""removable":false}],"#,
+                r#""interfaces":[{"name":"eth0","mac_address":"52:54:00:12:34:56","#,
+                r#""speed":null,"mtu":1500,"operstate":"up"}]}"#
+            )
+        );
+    }
+
+    #[test]
+    fn exports_empty_lists_as_json() {
+        let json = Inventory::default().to_json();
+        assert!(json.contains(r#""caches":[]"#));
+        assert!(json.contains(r#""block_devices":[],"interfaces":[]}"#));
+        assert!(json.contains(r#""virtualization":null,"hypervisor":null"#));
+    }
+}
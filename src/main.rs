@@ -1,27 +1,147 @@
-use std::{collections::HashMap, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt, fs,
+    path::{Path, PathBuf},
+    process::{Child, Command, ExitStatus, Stdio},
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
-use color_eyre::Result;
+use color_eyre::{eyre::bail, Result};
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::{
     layout::{Alignment, Constraint::*, Direction, Flex, Layout, Rect},
-    style::{palette::tailwind, Style, Stylize},
+    style::{palette::tailwind, Color, Style, Stylize},
     symbols::Marker,
-    text::Line,
+    text::{Line, Span},
     widgets::{
-        Axis, Bar, BarChart, BarGroup, Block, BorderType, Chart, Clear, Dataset, GraphType,
+        Axis, Bar, BarChart, BarGroup, Block, BorderType, Cell, Chart, Clear, Dataset, GraphType,
         RenderDirection, Row, Sparkline, Table, TableState,
     },
     DefaultTerminal, Frame,
 };
-use sysinfo::{Disks, Networks, ProcessesToUpdate, System};
+use sysinfo::{
+    Disks, Networks, Pid, Process, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, System,
+    UpdateKind, Users,
+};
 use tui_textarea::TextArea;
 
+use crate::{
+    cgroups::Cgroup,
+    containers::Container,
+    explorer::{Node, Scan},
+    interfaces::Link,
+    inventory::Inventory,
+    pressure::{Pressure, Resource},
+    sockets::Socket,
+};
+
+mod cgroups;
+mod containers;
+mod explorer;
+mod interfaces;
+mod inventory;
+mod pressure;
+mod sockets;
+mod treemap;
+mod vmstat;
+
 fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
+    let args = Args::parse(std::env::args().skip(1))?;
+    if let Some(format) = args.inventory {
+        let inventory = Inventory::read();
+        match format {
+            InventoryFormat::Text => print!("{}", inventory.to_text()),
+            InventoryFormat::Json => println!("{}", inventory.to_json()),
+        }
+        return Ok(());
+    }
+    let mut app = App::new();
+    if let Some(horizon) = args.disk_horizon {
+        app.disk_horizon = horizon;
+    }
+    if !args.command.is_empty() {
+        app.launch(&args.command)?;
+    } else if let Some(pid) = args.pid {
+        app.attach(pid)?;
+    }
     let terminal = ratatui::init();
-    let result = App::new().run(terminal);
+    let result = app.run(terminal);
     ratatui::restore();
-    result
+    if let Some(report) = result? {
+        println!("{report}");
+        if let Some(code) = report.status.and_then(exit_code) {
+            std::process::exit(code);
+        }
+    }
+    Ok(())
+}
+
+/// Command line arguments.
+#[derive(Debug, Default)]
+struct Args {
+    /// The command to launch and monitor, given after `--`.
+    command: Vec<String>,
+    /// The process to attach to, given with `--pid`.
+    pid: Option<Pid>,
+    /// How soon a disk must be forecast to fill up to be alerted, given with
+    /// `--disk-horizon`.
+    disk_horizon: Option<Duration>,
+    /// The format the inventory is printed in instead of running, given with `--inventory`.
+    inventory: Option<InventoryFormat>,
+}
+
+/// The format of the exported inventory.
+#[derive(Clone, Copy, Debug)]
+enum InventoryFormat {
+    Text,
+    Json,
+}
+
+impl Args {
+    /// Parses the given command line arguments.
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut parsed = Self::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--" => parsed.command = args.by_ref().collect(),
+                "-p" | "--pid" => {
+                    let Some(pid) = args.next() else {
+                        bail!("missing value for {arg}");
+                    };
+                    parsed.pid = Some(pid.parse()?);
+                }
+                "--disk-horizon" => {
+                    let Some(horizon) = args.next() else {
+                        bail!("missing value for {arg}");
+                    };
+                    parsed.disk_horizon = Some(parse_duration(&horizon)?);
+                }
+                "--inventory" => {
+                    parsed.inventory = match args.next().as_deref() {
+                        Some("text") => Some(InventoryFormat::Text),
+                        Some("json") => Some(InventoryFormat::Json),
+                        Some(format) => bail!("unknown inventory format: {format}"),
+                        None => bail!("missing value for {arg}"),
+                    };
+                }
+                "-h" | "--help" => {
+                    println!(
+                        "Usage: ratatop [--pid <pid>] [--disk-horizon <duration>] \
+                         [--inventory <text|json>] [-- <command>...]"
+                    );
+                    std::process::exit(0);
+                }
+                _ => bail!("unexpected argument: {arg}"),
+            }
+        }
+        if parsed.pid.is_some() && !parsed.command.is_empty() {
+            bail!("--pid cannot be combined with a command");
+        }
+        Ok(parsed)
+    }
 }
 
 /// The main application which holds the state and logic of the application.
@@ -29,102 +149,1151 @@ fn main() -> color_eyre::Result<()> {
 pub struct App {
     /// Is the application running?
     running: bool,
+    /// Is the refresh of the data paused?
+    paused: bool,
     /// Is the user searching?
     searching: bool,
+    /// Is the full command line shown instead of the process name?
+    show_command: bool,
+    /// The horizontal scroll offset of the command column.
+    horizontal_scroll: usize,
+    /// Are the CPU and memory history columns shown?
+    show_sparklines: bool,
+    /// Does the process table show one row per container?
+    group_by_container: bool,
+    /// The container the process table is filtered to, `None` being the host.
+    container_filter: Option<Option<Container>>,
+    /// How the CPU usage of the processes is accounted.
+    cpu_mode: CpuMode,
+    /// The process shown in the detail view.
+    inspected_pid: Option<Pid>,
+    /// The TCP and UDP sockets of the inspected process.
+    connections: Vec<Socket>,
+    /// The screen shown below the header.
+    screen: Screen,
+    /// The dashboard pane that receives the navigation keys.
+    focus: Focus,
     /// The current state of the table.
     table_state: TableState,
+    /// The current state of the event log.
+    event_table_state: TableState,
+    /// The current state of the leak suspects table.
+    suspect_table_state: TableState,
+    /// The current state of the top consumers table.
+    ranking_table_state: TableState,
+    /// The time window of the top consumers.
+    ranking_window: RankingWindow,
+    /// What the top consumers are ranked by.
+    ranking_metric: RankingMetric,
+    /// The top consumers, merged from the usage buckets of the window and sorted, until the
+    /// buckets, the window or the metric change.
+    ranking: Option<Vec<(Pid, ProcessUsage)>>,
+    /// The process to select in the process table once it is rendered.
+    pending_selection: Option<Pid>,
+    /// The current state of the sockets table.
+    socket_table_state: TableState,
     /// The input area.
     textarea: TextArea<'static>,
+    /// The number of interfaces scrolled past in the network pane.
+    network_scroll: usize,
+    /// How the interfaces of the network pane are sorted.
+    network_sort: NetworkSort,
+    /// Whether the loopback interfaces are hidden from the network pane.
+    hide_loopback: bool,
+    /// Whether the interfaces whose link is down are hidden from the network pane.
+    hide_down: bool,
+    /// Whether the virtual interfaces, such as bridges and veths, are hidden from the network
+    /// pane.
+    hide_virtual: bool,
+    /// The name patterns of the interfaces shown in the network pane.
+    network_filter: TextArea<'static>,
 
     /// System information.
     system: System,
+    host: HostInfo,
+    /// Global CPU usage, refreshed more often than the CPU usage of the processes which is
+    /// computed over the time since the previous CPU refresh.
+    cpu_system: System,
     networks: Networks,
 
     /// Data collected from the system.
     cpu_data: Vec<CpuData>,
     memory_data: Vec<MemoryData>,
     disk_data: Vec<DiskData>,
+    /// Used space of each filesystem by mount point, sampled for the disk-full forecast.
+    disk_history: HashMap<PathBuf, Vec<DiskSample>>,
+    /// When the disks were last refreshed.
+    disk_refresh_time: Option<Instant>,
+    /// The disks being read in the background, until they are received.
+    disk_receiver: Option<Receiver<Vec<DiskData>>>,
+    /// How soon a disk must be forecast to fill up to be alerted.
+    disk_horizon: Duration,
+    /// What the top processes are ranked by.
+    top_metric: TopMetric,
+    /// What the memory pane shows.
+    memory_view: MemoryView,
+    /// The index of the selected cell of the memory treemap.
+    selected_treemap_cell: usize,
+    /// User names, refreshed when the treemap is grouped by user.
+    users: Users,
+    /// The index of the selected disk in the disks pane.
+    selected_disk: usize,
+    /// The directory scan of the selected disk.
+    scan: Option<Scan>,
+    /// The names of the directories browsed into from the root of the scan.
+    explorer_path: Vec<String>,
+    /// The current state of the directory table.
+    explorer_table_state: TableState,
+    /// The current state of the cgroup tree.
+    cgroup_table_state: TableState,
+    /// The mount point of the cgroup v2 hierarchy, if mounted.
+    cgroup_root: Option<PathBuf>,
+    /// The cgroup v2 hierarchy, refreshed while it is shown.
+    cgroups: Vec<CgroupDetails>,
+    /// When the cgroups were last refreshed.
+    cgroup_refresh_time: Option<Instant>,
+    /// The hardware and system inventory, read when it is shown.
+    inventory: Option<Inventory>,
+    /// The current state of the inventory.
+    inventory_table_state: TableState,
+    /// Where the inventory was last exported to, or why it could not be.
+    inventory_export: Option<String>,
+    /// The cgroup the process table is filtered to.
+    cgroup_filter: Option<String>,
+    /// The threads of the processes in the filtered cgroup and its descendants.
+    cgroup_threads: HashSet<Pid>,
     network_data: HashMap<String, Vec<NetworkData>>,
+    /// Pressure on each resource, empty when the kernel does not report it.
+    pressure_data: HashMap<Resource, Vec<PressureData>>,
+    /// The previous paging and swapping counters and when they were read.
+    vmstat_counters: Option<(Instant, vmstat::Counters)>,
+    /// Paging and swapping rates, empty when the kernel does not report them.
+    vmstat_data: Vec<VmstatData>,
+    /// The previous count of OOM kills since boot.
+    oom_kills: Option<u64>,
+    /// The previous count of OOM kills in each cgroup.
+    cgroup_oom_kills: HashMap<String, u64>,
+    /// Processes killed by the OOM killer, the most recent last.
+    oom_events: Vec<OomEvent>,
+    /// The current state of the OOM kill table.
+    oom_table_state: TableState,
+    process_data: HashMap<Pid, Vec<ProcessData>>,
+    /// When the processes were last refreshed.
+    process_refresh_time: Option<Instant>,
+    /// The time between the last two refreshes of the processes.
+    process_refresh_interval: Duration,
+    /// The PIDs seen on the previous refresh.
+    known_pids: HashSet<Pid>,
+    /// What is known about each process, kept until it exits.
+    process_summaries: HashMap<Pid, ProcessSummary>,
+    /// Spawned and exited processes, the most recent last.
+    process_events: Vec<ProcessEvent>,
+    /// The command launched by ratatop.
+    command: Option<LaunchedCommand>,
+    /// The process whose tree the dashboard is focused on.
+    scope: Option<Pid>,
+    /// Resident memory samples of each process for leak detection.
+    memory_trends: HashMap<Pid, Vec<MemorySample>>,
+    /// When the memory trends were last sampled.
+    memory_trend_time: Option<Instant>,
+    /// Processes whose memory keeps growing, the fastest first.
+    leak_suspects: Vec<LeakSuspect>,
+    /// Resource usage of each process per minute, the most recent last.
+    usage_buckets: Vec<UsageBucket>,
+    /// Listening sockets and their owning processes, the main one first, refreshed while they
+    /// are shown.
+    sockets: Vec<(Socket, Vec<Pid>)>,
+    /// The current state of the interfaces table.
+    interface_table_state: TableState,
+    /// The link and counter rates of each network interface.
+    interfaces: HashMap<String, InterfaceDetails>,
+}
+
+/// The screen shown below the header.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Screen {
+    #[default]
+    Dashboard,
+    Events,
+    Suspects,
+    Ranking,
+    Sockets,
+    Interfaces,
+    Explorer,
+    Pressure,
+    Cgroups,
+    Inventory,
+    Oom,
+}
+
+/// What is shown about the host in the header, read once at startup.
+#[derive(Clone, Debug, Default)]
+struct HostInfo {
+    name: String,
+    os_version: String,
+    kernel_version: String,
+    /// Seconds since the epoch.
+    boot_time: u64,
+}
+
+impl HostInfo {
+    fn read() -> Self {
+        Self {
+            name: System::host_name().unwrap_or_else(|| "unknown".to_string()),
+            os_version: [System::name(), System::os_version()]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(" "),
+            kernel_version: System::kernel_version().unwrap_or_default(),
+            boot_time: System::boot_time(),
+        }
+    }
+}
+
+/// Aggregated usage of the process tree the dashboard is focused on.
+#[derive(Clone, Copy, Debug, Default)]
+struct ScopeUsage {
+    /// CPU usage where a full core is 100%.
+    cpu_usage: f64,
+    memory: u64,
+    /// Bytes read and written per second.
+    io_rate: f64,
+}
+
+/// A command launched and monitored by ratatop.
+#[derive(Debug)]
+struct LaunchedCommand {
+    child: Child,
+    pid: Pid,
+    command: String,
+    /// The file the output of the command is written to.
+    log_path: PathBuf,
+    started: Instant,
+    /// The exit status, once the command has exited.
+    status: Option<ExitStatus>,
+    /// Resource usage of each process of the tree, including the exited ones.
+    usage: HashMap<Pid, TreeUsage>,
+    /// The highest total resident memory of the tree.
+    peak_memory: u64,
+}
+
+/// Resource usage of a process in the tree of a launched command.
+#[derive(Clone, Debug, Default)]
+struct TreeUsage {
+    name: String,
+    /// CPU time in seconds, where a second of a full core is one.
+    cpu_time: f64,
+    peak_memory: u64,
+    read_bytes: u64,
+    written_bytes: u64,
+    /// Whether the process was reaped by a parent in the tree, which then accounts for its
+    /// I/O as well.
+    reaped_in_tree: bool,
+}
+
+/// The summary printed when a launched command exits.
+#[derive(Debug)]
+pub struct CommandReport {
+    command: String,
+    log_path: PathBuf,
+    status: Option<ExitStatus>,
+    wall_time: Duration,
+    cpu_time: f64,
+    peak_memory: u64,
+    read_bytes: u64,
+    written_bytes: u64,
+    /// The processes that used the most CPU time, the busiest first.
+    busiest: Vec<(Pid, TreeUsage)>,
+}
+
+/// Number of processes listed in the command report.
+const REPORT_BUSIEST_LEN: usize = 5;
+
+impl fmt::Display for CommandReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Command:    {}", self.command)?;
+        match self.status {
+            Some(status) => writeln!(f, "Status:     {status}")?,
+            None => writeln!(f, "Status:     unknown")?,
+        }
+        writeln!(f, "Output:     {}", self.log_path.display())?;
+        writeln!(f, "Wall time:  {}", format_duration(self.wall_time))?;
+        writeln!(
+            f,
+            "CPU time:   {}",
+            format_duration(Duration::from_secs_f64(self.cpu_time))
+        )?;
+        writeln!(f, "Peak RSS:   {}", format_bytes(self.peak_memory))?;
+        writeln!(f, "Read:       {}", format_bytes(self.read_bytes))?;
+        writeln!(f, "Written:    {}", format_bytes(self.written_bytes))?;
+        writeln!(f)?;
+        writeln!(f, "Busiest processes:")?;
+        writeln!(
+            f,
+            "  {:<10} {:<10} {:<10} {:<10} {:<10} Name",
+            "Pid", "CPU time", "Peak RSS", "Read", "Written"
+        )?;
+        for (pid, usage) in &self.busiest {
+            writeln!(
+                f,
+                "  {:<10} {:<10} {:<10} {:<10} {:<10} {}",
+                pid.to_string(),
+                format_duration(Duration::from_secs_f64(usage.cpu_time)),
+                format_bytes(usage.peak_memory),
+                format_bytes(usage.read_bytes),
+                format_bytes(usage.written_bytes),
+                usage.name
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// How the CPU usage of the processes is accounted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum CpuMode {
+    /// A full core is 100%, so a process can go over 100% on multicore machines.
+    #[default]
+    PerCore,
+    /// The whole machine is 100%, like the CPU chart.
+    Machine,
+}
+
+impl CpuMode {
+    fn toggle(self) -> Self {
+        match self {
+            Self::PerCore => Self::Machine,
+            Self::Machine => Self::PerCore,
+        }
+    }
+}
+
+/// The dashboard pane that receives the navigation keys.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Focus {
+    #[default]
+    Processes,
+    Network,
+    Disks,
+    Memory,
+}
+
+impl Focus {
+    fn next(self) -> Self {
+        match self {
+            Self::Processes => Self::Network,
+            Self::Network => Self::Disks,
+            Self::Disks => Self::Memory,
+            Self::Memory => Self::Processes,
+        }
+    }
+}
+
+/// What the top processes are ranked by.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum TopMetric {
+    #[default]
+    Cpu,
+    Memory,
+    /// Bytes read and written per second.
+    DiskIo,
+    Threads,
+}
+
+impl TopMetric {
+    fn next(self) -> Self {
+        match self {
+            Self::Cpu => Self::Memory,
+            Self::Memory => Self::DiskIo,
+            Self::DiskIo => Self::Threads,
+            Self::Threads => Self::Cpu,
+        }
+    }
+}
+
+impl fmt::Display for TopMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Cpu => "CPU",
+            Self::Memory => "Memory",
+            Self::DiskIo => "Disk I/O",
+            Self::Threads => "Threads",
+        })
+    }
+}
+
+/// What the memory pane shows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum MemoryView {
+    /// The history of the used memory.
+    #[default]
+    Chart,
+    /// The history of paging, swapping and reclaim.
+    Activity,
+    /// A treemap of the resident memory of each process.
+    Processes,
+    /// The treemap grouped by user.
+    Users,
+    /// The treemap grouped by parent process.
+    Parents,
+}
+
+impl MemoryView {
+    fn next(self) -> Self {
+        match self {
+            Self::Chart => Self::Activity,
+            Self::Activity => Self::Processes,
+            Self::Processes => Self::Users,
+            Self::Users => Self::Parents,
+            Self::Parents => Self::Chart,
+        }
+    }
+}
+
+/// Processes of the memory treemap sharing a user or parent.
+#[derive(Clone, Debug)]
+struct MemoryGroup {
+    name: String,
+    memory: u64,
+    /// PID, name and resident memory of each process, the largest first.
+    processes: Vec<(Pid, String, u64)>,
+}
+
+/// How the interfaces of the network pane are sorted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum NetworkSort {
+    #[default]
+    Name,
+    /// The busiest interfaces first.
+    Throughput,
+}
+
+impl NetworkSort {
+    fn toggle(self) -> Self {
+        match self {
+            Self::Name => Self::Throughput,
+            Self::Throughput => Self::Name,
+        }
+    }
+}
+
+/// A condition of the process filter such as `cpu>50`.
+#[derive(Clone, Copy, Debug)]
+struct FilterCondition {
+    /// The index of the compared column.
+    column: usize,
+    ordering: std::cmp::Ordering,
+    /// Whether an equal value also matches.
+    or_equal: bool,
+    value: f64,
+}
+
+impl FilterCondition {
+    /// Parses a condition such as `cpu>50` or `mem<=10`.
+    fn parse(term: &str) -> Option<Self> {
+        let index = term.find(['<', '>'])?;
+        let (field, rest) = term.split_at(index);
+        let column = match field.to_lowercase().as_str() {
+            "cpu" => 2,
+            "mem" => 3,
+            _ => return None,
+        };
+        let ordering = if rest.starts_with('>') {
+            std::cmp::Ordering::Greater
+        } else {
+            std::cmp::Ordering::Less
+        };
+        let (or_equal, value) = match rest[1..].strip_prefix('=') {
+            Some(value) => (true, value),
+            None => (false, &rest[1..]),
+        };
+        Some(Self {
+            column,
+            ordering,
+            or_equal,
+            value: value.parse().ok()?,
+        })
+    }
+
+    fn matches(&self, row: &[String]) -> bool {
+        let Some(value) = row.get(self.column).and_then(|v| v.parse::<f64>().ok()) else {
+            return false;
+        };
+        match value.partial_cmp(&self.value) {
+            Some(std::cmp::Ordering::Equal) => self.or_equal,
+            Some(ordering) => ordering == self.ordering,
+            None => false,
+        }
+    }
+}
+
+/// The time window of the top consumers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum RankingWindow {
+    #[default]
+    FiveMinutes,
+    FifteenMinutes,
+    OneHour,
+}
+
+impl RankingWindow {
+    fn minutes(self) -> usize {
+        match self {
+            Self::FiveMinutes => 5,
+            Self::FifteenMinutes => 15,
+            Self::OneHour => 60,
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            Self::FiveMinutes => Self::FifteenMinutes,
+            Self::FifteenMinutes => Self::OneHour,
+            Self::OneHour => Self::FiveMinutes,
+        }
+    }
+}
+
+/// What the top consumers are ranked by.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum RankingMetric {
+    #[default]
+    CpuTime,
+    AverageMemory,
+    PeakMemory,
+}
+
+impl RankingMetric {
+    fn next(self) -> Self {
+        match self {
+            Self::CpuTime => Self::AverageMemory,
+            Self::AverageMemory => Self::PeakMemory,
+            Self::PeakMemory => Self::CpuTime,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
 struct CpuData {
     usage: f64,
+    /// Share of the process tree the dashboard is focused on.
+    scope_usage: f64,
     point: f64,
 }
 
 #[derive(Clone, Debug)]
 struct MemoryData {
     usage: f64,
+    /// Share of the process tree the dashboard is focused on.
+    scope_usage: f64,
     point: f64,
 }
 
 #[derive(Clone, Debug)]
 struct DiskData {
     name: String,
+    mount_point: PathBuf,
     available: u64,
     total: u64,
+    /// How long until the disk is full at the current trend, if it is growing.
+    full_in: Option<Duration>,
+    inodes: u64,
+    free_inodes: u64,
+    read_only: bool,
+    no_exec: bool,
+    removable: bool,
+    /// Whether the filesystem is mounted over the network, e.g. NFS.
+    remote: bool,
+}
+
+impl DiskData {
+    /// Returns the percentage of used space.
+    fn used_percent(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        (self.total - self.available.min(self.total)) as f64 / self.total as f64 * 100.0
+    }
+
+    /// Returns the percentage of used inodes, if the filesystem has a fixed number of them.
+    fn inode_percent(&self) -> Option<f64> {
+        if self.inodes == 0 {
+            return None;
+        }
+        let used = self.inodes - self.free_inodes.min(self.inodes);
+        Some(used as f64 / self.inodes as f64 * 100.0)
+    }
+
+    /// Returns the flags worth noticing such as `ro` or `remote`.
+    fn flags(&self) -> Vec<&'static str> {
+        [
+            (self.read_only, "ro"),
+            (self.no_exec, "noexec"),
+            (self.removable, "removable"),
+            (self.remote, "remote"),
+        ]
+        .into_iter()
+        .filter_map(|(set, flag)| set.then_some(flag))
+        .collect()
+    }
+}
+
+/// Used space of a filesystem at a point in time.
+#[derive(Clone, Copy, Debug)]
+struct DiskSample {
+    time: Instant,
+    used: u64,
 }
 
+/// Inode counts and mount flags of a filesystem.
+#[derive(Clone, Copy, Debug, Default)]
+struct FilesystemStats {
+    inodes: u64,
+    free_inodes: u64,
+    read_only: bool,
+    no_exec: bool,
+}
+
+/// Filesystems mounted over the network.
+const REMOTE_FILE_SYSTEMS: [&str; 10] = [
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb3",
+    "smbfs",
+    "sshfs",
+    "fuse.sshfs",
+    "ceph",
+    "glusterfs",
+    "9p",
+];
+
 #[derive(Clone, Debug)]
 struct NetworkData {
     total_packets: u64,
 }
 
+/// A cgroup along with the rates of its counters since the previous refresh.
+#[derive(Clone, Debug)]
+struct CgroupDetails {
+    cgroup: Cgroup,
+    /// CPU usage where a full core is 100%.
+    cpu_usage: f64,
+    /// Share of the enforcement periods in which the cgroup was throttled.
+    throttled: f64,
+}
+
+impl CgroupDetails {
+    /// Returns the CPU limit where a full core is 100%, if any.
+    fn cpu_limit(&self) -> Option<f64> {
+        let (quota, period) = self.cgroup.cpu_max?;
+        (period > 0).then(|| quota as f64 / period as f64 * 100.0)
+    }
+}
+
+/// A process killed by the OOM killer.
+#[derive(Clone, Debug)]
+struct OomEvent {
+    time: SystemTime,
+    /// The deepest cgroup whose `memory.events` counted the kill, if any.
+    cgroup: Option<String>,
+    /// The process that exited around the kill.
+    victim: Option<OomVictim>,
+}
+
+/// The process an OOM kill was attributed to.
+#[derive(Clone, Debug)]
+struct OomVictim {
+    pid: Pid,
+    name: String,
+    /// The last known resident memory.
+    memory: u64,
+    /// Whether the process was only taken for the victim because the cgroup of the kill is
+    /// unknown, so that any process that exited around it could have been killed.
+    likely: bool,
+}
+
+#[derive(Clone, Debug)]
+struct VmstatData {
+    rates: vmstat::Rates,
+    point: f64,
+}
+
+#[derive(Clone, Debug)]
+struct PressureData {
+    pressure: Pressure,
+    point: f64,
+}
+
+/// The link of a network interface along with the rates of its counters.
+#[derive(Clone, Debug)]
+struct InterfaceDetails {
+    link: Link,
+    time: Instant,
+    /// Bytes received per second.
+    receive_rate: f64,
+    /// Bytes transmitted per second.
+    transmit_rate: f64,
+    /// Errors per second.
+    error_rate: f64,
+    /// Dropped packets per second.
+    drop_rate: f64,
+    /// Collisions per second.
+    collision_rate: f64,
+}
+
+impl InterfaceDetails {
+    /// Returns the busiest direction as a percentage of the link speed, if known.
+    ///
+    /// Links are assumed to be full duplex.
+    fn utilization(&self) -> Option<f64> {
+        let speed = self.link.speed? as f64 * 1_000_000.0 / 8.0;
+        Some(self.receive_rate.max(self.transmit_rate) / speed * 100.0)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct ProcessData {
+    cpu_usage: f64,
+    memory: u64,
+    threads: usize,
+    /// Bytes read and written per second.
+    io_rate: f64,
+    time: Instant,
+    point: f64,
+}
+
+#[derive(Clone, Debug)]
+struct ProcessSummary {
+    name: String,
+    parent: Option<Pid>,
+    start_time: SystemTime,
+    memory: u64,
+    /// `None` until the CPU usage is measured, which takes two refreshes.
+    peak_cpu_usage: Option<f64>,
+    peak_memory: u64,
+    /// The container, pod or systemd unit the process runs in.
+    container: Option<Container>,
+    /// The path of the process in the cgroup v2 hierarchy.
+    cgroup: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ProcessEventKind {
+    Spawned,
+    Exited,
+}
+
+#[derive(Clone, Debug)]
+struct ProcessEvent {
+    kind: ProcessEventKind,
+    time: SystemTime,
+    pid: Pid,
+    summary: ProcessSummary,
+}
+
+/// Number of events kept in the event log.
+const EVENT_LOG_LEN: usize = 1000;
+
+/// How long spawned and exited processes are highlighted in the process table.
+const EVENT_HIGHLIGHT_DURATION: Duration = Duration::from_secs(3);
+
+#[derive(Clone, Copy, Debug)]
+struct MemorySample {
+    time: Instant,
+    memory: u64,
+}
+
+/// A process with a sustained growth of its resident memory.
+#[derive(Clone, Debug)]
+struct LeakSuspect {
+    pid: Pid,
+    /// Growth of the resident memory in bytes per hour.
+    growth_rate: f64,
+    /// Resident memory at the start of the trend line.
+    intercept: f64,
+}
+
+/// Resource usage of a process accumulated over some time.
+#[derive(Clone, Debug, Default)]
+struct ProcessUsage {
+    name: String,
+    /// CPU time in seconds, where a second of a full core is one.
+    cpu_time: f64,
+    /// Sum of the sampled resident memory.
+    memory_total: f64,
+    samples: u64,
+    peak_memory: u64,
+}
+
+impl ProcessUsage {
+    fn merge(&mut self, other: &Self) {
+        self.name.clone_from(&other.name);
+        self.cpu_time += other.cpu_time;
+        self.memory_total += other.memory_total;
+        self.samples += other.samples;
+        self.peak_memory = self.peak_memory.max(other.peak_memory);
+    }
+
+    fn average_memory(&self) -> u64 {
+        if self.samples == 0 {
+            return 0;
+        }
+        (self.memory_total / self.samples as f64) as u64
+    }
+}
+
+/// Resource usage of the processes during a minute.
+#[derive(Clone, Debug)]
+struct UsageBucket {
+    start: Instant,
+    usage: HashMap<Pid, ProcessUsage>,
+}
+
+/// How long the resource usage of each process is accumulated in a bucket.
+const USAGE_BUCKET_DURATION: Duration = Duration::from_secs(60);
+
+/// Number of usage buckets kept, enough for the longest ranking window.
+const USAGE_BUCKET_LEN: usize = 60;
+
+/// How often the resident memory of each process is sampled for leak detection.
+const LEAK_SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Number of samples kept for leak detection, an hour in total.
+const LEAK_SAMPLE_LEN: usize = 360;
+
+/// Number of samples needed before a process can be flagged.
+const LEAK_MIN_SAMPLES: usize = 12;
+
+/// Share of the samples that must not be lower than the previous one.
+const LEAK_MIN_MONOTONICITY: f64 = 0.9;
+
+/// Growth rate in bytes per hour below which a process is not flagged.
+const LEAK_MIN_GROWTH_RATE: f64 = 1024.0 * 1024.0;
+
+/// How often the disks are refreshed and their used space sampled for the forecast.
+const DISK_SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Number of samples kept for the disk-full forecast, an hour in total.
+const DISK_SAMPLE_LEN: usize = 360;
+
+/// Number of samples needed before a disk-full forecast is made.
+const DISK_MIN_SAMPLES: usize = 6;
+
+/// How soon a disk must be forecast to fill up to be alerted, unless configured.
+const DEFAULT_DISK_HORIZON: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Number of the largest processes shown in the memory treemap.
+const TREEMAP_LEN: usize = 64;
+
+/// Number of pressure samples kept for each resource.
+const PRESSURE_HISTORY_LEN: usize = 600;
+
+/// Number of paging and swapping samples kept.
+const VMSTAT_HISTORY_LEN: usize = 600;
+
+/// How far apart an OOM kill and the exit of its victim are looked for.
+///
+/// The kill and the exit of the victim may be noticed on different refreshes, in either
+/// order.
+const OOM_VICTIM_WINDOW: Duration = Duration::from_secs(3);
+
+/// How long the banner of an OOM kill is flashed.
+const OOM_BANNER_DURATION: Duration = Duration::from_secs(10);
+
+/// Number of samples kept for each process.
+const PROCESS_HISTORY_LEN: usize = 600;
+
+/// How long the samples of a process are kept after it exits, so that it can still be
+/// inspected from the event log.
+const EXITED_PROCESS_HISTORY_DURATION: Duration = Duration::from_secs(5 * 60);
+
+/// Number of samples shown in the inline sparklines of the process table.
+const SPARKLINE_LEN: usize = 10;
+
 impl App {
     /// Construct a new instance of [`App`].
     pub fn new() -> Self {
         Self {
             running: true,
+            paused: false,
             searching: false,
+            show_command: false,
+            horizontal_scroll: 0,
+            show_sparklines: false,
+            group_by_container: false,
+            container_filter: None,
+            cpu_mode: CpuMode::default(),
+            inspected_pid: None,
+            connections: Vec::new(),
+            screen: Screen::Dashboard,
+            focus: Focus::Processes,
             table_state: TableState::default(),
+            event_table_state: TableState::default(),
+            suspect_table_state: TableState::default(),
+            ranking_table_state: TableState::default(),
+            ranking_window: RankingWindow::default(),
+            ranking_metric: RankingMetric::default(),
+            ranking: None,
+            pending_selection: None,
+            socket_table_state: TableState::default(),
             textarea: {
                 let mut textarea = TextArea::default();
                 textarea.set_block(Self::create_pane("Search"));
                 textarea
             },
+            network_scroll: 0,
+            network_sort: NetworkSort::Name,
+            hide_loopback: false,
+            hide_down: false,
+            hide_virtual: false,
+            network_filter: {
+                let mut textarea = TextArea::default();
+                textarea.set_block(Self::create_pane("Interfaces (e.g. eth* !veth*)"));
+                textarea
+            },
             system: System::new_all(),
+            host: HostInfo::read(),
+            cpu_system: System::new(),
             networks: Networks::new(),
             cpu_data: Vec::new(),
             memory_data: Vec::new(),
             disk_data: Vec::new(),
+            disk_history: HashMap::new(),
+            disk_refresh_time: None,
+            disk_receiver: None,
+            disk_horizon: DEFAULT_DISK_HORIZON,
+            top_metric: TopMetric::Cpu,
+            memory_view: MemoryView::Chart,
+            selected_treemap_cell: 0,
+            users: Users::new(),
+            selected_disk: 0,
+            scan: None,
+            explorer_path: Vec::new(),
+            explorer_table_state: TableState::default(),
+            cgroup_table_state: TableState::default(),
+            cgroup_root: cgroups::root(),
+            cgroups: Vec::new(),
+            cgroup_refresh_time: None,
+            inventory: None,
+            inventory_table_state: TableState::default(),
+            inventory_export: None,
+            cgroup_filter: None,
+            cgroup_threads: HashSet::new(),
             network_data: HashMap::new(),
+            pressure_data: HashMap::new(),
+            vmstat_counters: None,
+            vmstat_data: Vec::new(),
+            oom_kills: None,
+            cgroup_oom_kills: HashMap::new(),
+            oom_events: Vec::new(),
+            oom_table_state: TableState::default(),
+            process_data: HashMap::new(),
+            process_refresh_time: None,
+            process_refresh_interval: Duration::ZERO,
+            known_pids: HashSet::new(),
+            process_summaries: HashMap::new(),
+            process_events: Vec::new(),
+            command: None,
+            scope: None,
+            memory_trends: HashMap::new(),
+            memory_trend_time: None,
+            leak_suspects: Vec::new(),
+            usage_buckets: Vec::new(),
+            sockets: Vec::new(),
+            interface_table_state: TableState::default(),
+            interfaces: HashMap::new(),
+        }
+    }
+
+    /// Launches the given command and focuses the dashboard on its process tree.
+    ///
+    /// The output of the command is written to a log file so that it does not garble the
+    /// interface.
+    pub fn launch(&mut self, command: &[String]) -> Result<()> {
+        let Some((program, args)) = command.split_first() else {
+            bail!("no command given");
+        };
+        let (log, log_path) = create_log()?;
+        let child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(log.try_clone()?)
+            .stderr(log)
+            .spawn()?;
+        let pid = Pid::from_u32(child.id());
+        self.scope = Some(pid);
+        self.command = Some(LaunchedCommand {
+            child,
+            pid,
+            command: command.join(" "),
+            log_path,
+            started: Instant::now(),
+            status: None,
+            usage: HashMap::new(),
+            peak_memory: 0,
+        });
+        Ok(())
+    }
+
+    /// Focuses the dashboard on the tree of the given process.
+    pub fn attach(&mut self, pid: Pid) -> Result<()> {
+        if self.system.process(pid).is_none() {
+            bail!("no process with PID {pid}");
         }
+        self.scope = Some(pid);
+        Ok(())
     }
 
     /// Run the application's main loop.
-    pub fn run(mut self, mut terminal: DefaultTerminal) -> Result<()> {
+    ///
+    /// Returns the report of the launched command, if any.
+    pub fn run(mut self, mut terminal: DefaultTerminal) -> Result<Option<CommandReport>> {
         self.refresh_disks();
         self.table_state.select(Some(0));
+        self.event_table_state.select(Some(0));
+        self.suspect_table_state.select(Some(0));
+        self.ranking_table_state.select(Some(0));
+        self.socket_table_state.select(Some(0));
+        self.interface_table_state.select(Some(0));
+        self.cgroup_table_state.select(Some(0));
+        self.inventory_table_state.select(Some(0));
+        self.oom_table_state.select(Some(0));
         while self.running {
             terminal.draw(|frame| {
                 self.render(frame);
-                self.refresh(frame.count());
+                if self.paused {
+                    self.refresh_trackers(frame.count());
+                } else {
+                    self.refresh(frame.count());
+                }
             })?;
             self.handle_crossterm_events()?;
+            self.wait_command()?;
+        }
+        self.finish_command()
+    }
+
+    /// Stops the application once the launched command has exited.
+    fn wait_command(&mut self) -> Result<()> {
+        let Some(command) = &mut self.command else {
+            return Ok(());
+        };
+        if let Some(status) = command.child.try_wait()? {
+            command.status = Some(status);
+            self.running = false;
         }
         Ok(())
     }
 
+    /// Kills the launched command if it is still running and creates its report.
+    fn finish_command(&mut self) -> Result<Option<CommandReport>> {
+        let Some(mut command) = self.command.take() else {
+            return Ok(None);
+        };
+        if command.status.is_none() {
+            command.child.kill()?;
+            command.status = Some(command.child.wait()?);
+        }
+
+        let mut busiest = command.usage.into_iter().collect::<Vec<_>>();
+        busiest.sort_by(|a, b| b.1.cpu_time.total_cmp(&a.1.cpu_time));
+        // The command is the only child of ratatop, and the kernel accounts for the processes
+        // it reaped, so its total is exact while the sampled one misses short-lived processes.
+        let cpu_time = children_cpu_time()
+            .unwrap_or_else(|| busiest.iter().map(|(_, usage)| usage.cpu_time).sum());
+        let report = CommandReport {
+            command: command.command,
+            log_path: command.log_path,
+            status: command.status,
+            wall_time: command.started.elapsed(),
+            cpu_time,
+            peak_memory: command.peak_memory,
+            read_bytes: busiest
+                .iter()
+                .filter(|(_, usage)| !usage.reaped_in_tree)
+                .map(|(_, usage)| usage.read_bytes)
+                .sum(),
+            written_bytes: busiest
+                .iter()
+                .filter(|(_, usage)| !usage.reaped_in_tree)
+                .map(|(_, usage)| usage.written_bytes)
+                .sum(),
+            busiest: busiest.into_iter().take(REPORT_BUSIEST_LEN).collect(),
+        };
+        Ok(Some(report))
+    }
+
+    /// Starts reading the disks in the background, unless the previous read is still
+    /// pending, e.g. on a hung NFS mount.
     pub fn refresh_disks(&mut self) {
-        let disks = Disks::new_with_refreshed_list();
-        self.disk_data = disks
-            .list()
-            .iter()
-            .map(|disk| DiskData {
-                name: disk.name().to_string_lossy().to_string(),
-                available: disk.available_space(),
-                total: disk.total_space(),
-            })
-            .collect();
+        if self.disk_receiver.is_some() {
+            return;
+        }
+        self.disk_refresh_time = Some(Instant::now());
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || sender.send(read_disks()));
+        self.disk_receiver = Some(receiver);
+    }
+
+    /// Receives the disks once they are read and forecasts when the growing ones will be
+    /// full.
+    fn poll_disks(&mut self) {
+        let Some(receiver) = &self.disk_receiver else {
+            return;
+        };
+        self.disk_data = match receiver.try_recv() {
+            Ok(disks) => disks,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => {
+                self.disk_receiver = None;
+                return;
+            }
+        };
+        self.disk_receiver = None;
+        let now = Instant::now();
+
+        self.disk_history.retain(|mount_point, _| {
+            self.disk_data
+                .iter()
+                .any(|disk| &disk.mount_point == mount_point)
+        });
+        for disk in &mut self.disk_data {
+            let samples = self
+                .disk_history
+                .entry(disk.mount_point.clone())
+                .or_default();
+            samples.push(DiskSample {
+                time: now,
+                used: disk.total.saturating_sub(disk.available),
+            });
+            if samples.len() > DISK_SAMPLE_LEN {
+                samples.remove(0);
+            }
+            if samples.len() < DISK_MIN_SAMPLES {
+                continue;
+            }
+            let start = samples[0].time;
+            let points = samples
+                .iter()
+                .map(|v| (v.time.duration_since(start).as_secs_f64(), v.used as f64))
+                .collect::<Vec<_>>();
+            let (slope, _) = linear_fit(&points);
+            if slope > 0.0 {
+                disk.full_in = Duration::try_from_secs_f64(disk.available as f64 / slope).ok();
+            }
+        }
     }
 
     /// Refresh the system information.
     pub fn refresh(&mut self, frame_count: usize) {
-        self.system.refresh_cpu_all();
-        let cpu_usage = self.system.global_cpu_usage();
+        self.cpu_system.refresh_cpu_all();
+        let cpu_usage = self.cpu_system.global_cpu_usage();
+        let scope_usage = self.scope_usage().unwrap_or_default();
         self.cpu_data.push(CpuData {
             usage: cpu_usage as f64,
+            scope_usage: scope_usage.cpu_usage / self.system.cpus().len().max(1) as f64,
             point: frame_count as f64,
         });
 
@@ -132,9 +1301,21 @@ impl App {
         let memory_usage = self.system.used_memory();
         self.memory_data.push(MemoryData {
             usage: memory_usage as f64,
+            scope_usage: scope_usage.memory as f64,
             point: frame_count as f64,
         });
 
+        if let Some(scan) = &mut self.scan {
+            scan.poll();
+        }
+        self.poll_disks();
+        if self
+            .disk_refresh_time
+            .is_none_or(|time| time.elapsed() >= DISK_SAMPLE_INTERVAL)
+        {
+            self.refresh_disks();
+        }
+
         self.networks.refresh(true);
         for (interface_name, network) in &self.networks {
             self.network_data
@@ -144,20 +1325,690 @@ impl App {
                     total_packets: network.packets_received() + network.packets_transmitted(),
                 });
         }
+        self.network_data
+            .retain(|name, _| self.networks.contains_key(name));
+
+        // New interfaces are read right away so that the toggles apply to them.
+        let has_unread_interfaces = self
+            .networks
+            .keys()
+            .any(|name| !self.interfaces.contains_key(name));
+        if (frame_count.is_multiple_of(30) || has_unread_interfaces) && self.needs_interfaces() {
+            self.refresh_interfaces();
+        }
 
-        if frame_count % 30 == 0 {
-            self.system.refresh_processes(ProcessesToUpdate::All, true);
+        // Processes are diffed on every refresh so that short-lived ones are logged, while they
+        // are only fully refreshed at the process refresh rate.
+        self.refresh_process_events();
+        if frame_count.is_multiple_of(30) {
+            self.system.refresh_processes_specifics(
+                ProcessesToUpdate::All,
+                true,
+                Self::process_refresh_kind(),
+            );
+            self.refresh_process_data(frame_count);
+            self.refresh_pressure(frame_count);
+            self.refresh_vmstat(frame_count);
+            self.find_oom_victims();
+            if self.screen == Screen::Cgroups {
+                self.refresh_cgroups();
+            }
+            self.refresh_cgroup_threads();
+            if self.screen == Screen::Sockets {
+                self.refresh_sockets();
+            }
+            if let Some(pid) = self.inspected_pid {
+                self.connections = sockets::process_connections(pid);
+            }
         }
     }
 
-    /// Renders the user interface.
-    ///
-    /// Split the area into 6 parts: header, cpu, disk, memory, network, and process.
+    /// Keeps logging the spawned and exited processes and the OOM kills while the refresh of the
+    /// displayed data is paused, so that none are missed.
+    fn refresh_trackers(&mut self, frame_count: usize) {
+        self.refresh_process_events();
+        if !frame_count.is_multiple_of(30) {
+            return;
+        }
+        if let Some(counters) = vmstat::read_counters() {
+            self.refresh_oom_kills(counters.oom_kills);
+        }
+        self.find_oom_victims();
+    }
+
+    /// Whether the links of the network interfaces are used by what is shown, which are the
+    /// interfaces screen and the sorting and toggles of the network pane.
+    fn needs_interfaces(&self) -> bool {
+        match self.screen {
+            Screen::Interfaces => true,
+            Screen::Dashboard => {
+                self.network_sort == NetworkSort::Throughput
+                    || self.hide_loopback
+                    || self.hide_down
+                    || self.hide_virtual
+            }
+            _ => false,
+        }
+    }
+
+    /// Reads the link of each network interface and the rates of its counters since the
+    /// previous refresh.
+    fn refresh_interfaces(&mut self) {
+        let now = Instant::now();
+        let mut interfaces = HashMap::new();
+        for name in self.networks.keys() {
+            let link = interfaces::read_interface(name);
+            let mut details = InterfaceDetails {
+                link,
+                time: now,
+                receive_rate: 0.0,
+                transmit_rate: 0.0,
+                error_rate: 0.0,
+                drop_rate: 0.0,
+                collision_rate: 0.0,
+            };
+            if let Some(previous) = self.interfaces.get(name) {
+                let elapsed = now.duration_since(previous.time).as_secs_f64();
+                let rate =
+                    |current: u64, previous: u64| current.saturating_sub(previous) as f64 / elapsed;
+                if elapsed > 0.0 {
+                    let (current, previous) = (&details.link, &previous.link);
+                    details.receive_rate = rate(current.received_bytes, previous.received_bytes);
+                    details.transmit_rate =
+                        rate(current.transmitted_bytes, previous.transmitted_bytes);
+                    details.error_rate = rate(current.errors, previous.errors);
+                    details.drop_rate = rate(current.dropped, previous.dropped);
+                    details.collision_rate = rate(current.collisions, previous.collisions);
+                }
+            }
+            interfaces.insert(name.clone(), details);
+        }
+        self.interfaces = interfaces;
+    }
+
+    /// Reads the cgroup hierarchy and the rates of its counters since the previous refresh.
+    fn refresh_cgroups(&mut self) {
+        let Some(root) = &self.cgroup_root else {
+            self.cgroups.clear();
+            return;
+        };
+        let now = Instant::now();
+        let elapsed = self
+            .cgroup_refresh_time
+            .replace(now)
+            .map(|time| now.duration_since(time).as_secs_f64())
+            .unwrap_or_default();
+        let previous = self
+            .cgroups
+            .iter()
+            .map(|details| (details.cgroup.path.as_str(), &details.cgroup))
+            .collect::<HashMap<_, _>>();
+
+        let cgroups = cgroups::read_tree(root)
+            .into_iter()
+            .map(|cgroup| {
+                let (mut cpu_usage, mut throttled) = (0.0, 0.0);
+                if let Some(previous) = previous.get(cgroup.path.as_str()) {
+                    if elapsed > 0.0 {
+                        let usage = cgroup.cpu_usage.saturating_sub(previous.cpu_usage);
+                        cpu_usage = usage as f64 / 1_000_000.0 / elapsed * 100.0;
+                    }
+                    let periods = cgroup.periods.saturating_sub(previous.periods);
+                    if periods > 0 {
+                        let throttled_periods = cgroup
+                            .throttled_periods
+                            .saturating_sub(previous.throttled_periods);
+                        throttled = throttled_periods as f64 / periods as f64 * 100.0;
+                    }
+                }
+                CgroupDetails {
+                    cgroup,
+                    cpu_usage,
+                    throttled,
+                }
+            })
+            .collect();
+        self.cgroups = cgroups;
+    }
+
+    /// Finds the threads of the cgroup the process table is filtered to.
+    fn refresh_cgroup_threads(&mut self) {
+        let (Some(root), Some(path)) = (&self.cgroup_root, &self.cgroup_filter) else {
+            return;
+        };
+        self.cgroup_threads = cgroups::threads(root, path);
+    }
+
+    /// Samples the pressure on each resource.
+    fn refresh_pressure(&mut self, frame_count: usize) {
+        for resource in Resource::ALL {
+            let Some(pressure) = pressure::read_pressure(resource) else {
+                continue;
+            };
+            let data = self.pressure_data.entry(resource).or_default();
+            data.push(PressureData {
+                pressure,
+                point: frame_count as f64,
+            });
+            if data.len() > PRESSURE_HISTORY_LEN {
+                data.remove(0);
+            }
+        }
+    }
+
+    fn refresh_vmstat(&mut self, frame_count: usize) {
+        let Some(counters) = vmstat::read_counters() else {
+            return;
+        };
+        self.refresh_oom_kills(counters.oom_kills);
+        let now = Instant::now();
+        let previous = self.vmstat_counters.replace((now, counters));
+        let Some((time, previous)) = previous else {
+            return;
+        };
+        let elapsed = now.duration_since(time).as_secs_f64();
+        if elapsed <= 0.0 {
+            return;
+        }
+        self.vmstat_data.push(VmstatData {
+            rates: counters.rates_since(&previous, elapsed),
+            point: frame_count as f64,
+        });
+        if self.vmstat_data.len() > VMSTAT_HISTORY_LEN {
+            self.vmstat_data.remove(0);
+        }
+    }
+
+    /// Records an event for each OOM kill since the previous refresh.
+    fn refresh_oom_kills(&mut self, oom_kills: u64) {
+        let previous = self.oom_kills.replace(oom_kills);
+        // The cgroups are only walked to find where the kills happened.
+        let root = self.cgroup_root.as_deref();
+        let Some(previous) = previous else {
+            if let Some(root) = root {
+                self.cgroup_oom_kills = cgroups::oom_kills(root);
+            }
+            return;
+        };
+        if oom_kills <= previous {
+            return;
+        }
+
+        let cgroup_oom_kills = root.map(cgroups::oom_kills).unwrap_or_default();
+        let cgroup = cgroups::oom_kill_cgroup(&self.cgroup_oom_kills, &cgroup_oom_kills);
+        self.cgroup_oom_kills = cgroup_oom_kills;
+
+        let now = SystemTime::now();
+        for _ in previous..oom_kills {
+            self.oom_events.push(OomEvent {
+                time: now,
+                cgroup: cgroup.clone(),
+                victim: None,
+            });
+        }
+        let overflow = self.oom_events.len().saturating_sub(EVENT_LOG_LEN);
+        self.oom_events.drain(..overflow);
+    }
+
+    /// Looks for the victims of the recent OOM kills among the processes that exited around
+    /// them.
+    ///
+    /// The OOM killer picks the process using the most memory, so the largest one that exited
+    /// in the cgroup of the kill, or below it, is taken. When the cgroup is unknown, any process
+    /// could have been killed, so the victim is only likely.
+    fn find_oom_victims(&mut self) {
+        let mut victims = self
+            .oom_events
+            .iter()
+            .filter_map(|event| event.victim.as_ref().map(|victim| victim.pid))
+            .collect::<HashSet<_>>();
+        for oom_event in self.oom_events.iter_mut().rev() {
+            // Exits until the end of the window are noticed a little later.
+            let is_recent = oom_event
+                .time
+                .elapsed()
+                .is_ok_and(|elapsed| elapsed < OOM_VICTIM_WINDOW * 2);
+            if !is_recent {
+                break;
+            }
+            if oom_event.victim.is_some() {
+                continue;
+            }
+            let victim = self
+                .process_events
+                .iter()
+                .rev()
+                .filter(|event| {
+                    event.kind == ProcessEventKind::Exited && !victims.contains(&event.pid)
+                })
+                .filter(|event| {
+                    let Some(cgroup) = &oom_event.cgroup else {
+                        return true;
+                    };
+                    event
+                        .summary
+                        .cgroup
+                        .as_ref()
+                        .is_some_and(|path| cgroups::is_within(path, cgroup))
+                })
+                .filter(|event| {
+                    let distance = match event.time.duration_since(oom_event.time) {
+                        Ok(distance) => distance,
+                        Err(error) => error.duration(),
+                    };
+                    distance <= OOM_VICTIM_WINDOW
+                })
+                .max_by_key(|event| event.summary.memory);
+            if let Some(event) = victim {
+                victims.insert(event.pid);
+                oom_event.victim = Some(OomVictim {
+                    pid: event.pid,
+                    name: event.summary.name.clone(),
+                    memory: event.summary.memory,
+                    likely: oom_event.cgroup.is_none(),
+                });
+            }
+        }
+    }
+
+    /// Reads the listening sockets and finds their owning process.
+    fn refresh_sockets(&mut self) {
+        let mut owners = sockets::socket_owners(self.process_summaries.keys().copied());
+        let mut listening = sockets::read_sockets(self.process_summaries.keys().copied())
+            .into_iter()
+            .filter(Socket::is_listening)
+            .map(|socket| {
+                let mut owners = owners.remove(&socket.inode).unwrap_or_default();
+                // The main process is the one that shared the socket with the others, so
+                // its parent does not own it.
+                let main = owners.iter().position(|pid| {
+                    self.process_summaries
+                        .get(pid)
+                        .and_then(|summary| summary.parent)
+                        .is_none_or(|parent| !owners.contains(&parent))
+                });
+                if let Some(main) = main {
+                    owners[..=main].rotate_right(1);
+                }
+                (socket, owners)
+            })
+            .collect::<Vec<_>>();
+        listening.sort_by(|(a, _), (b, _)| {
+            (a.protocol, a.local_port(), &a.local_address).cmp(&(
+                b.protocol,
+                b.local_port(),
+                &b.local_address,
+            ))
+        });
+        self.sockets = listening;
+    }
+
+    /// Returns what is refreshed for each process.
+    fn process_refresh_kind() -> ProcessRefreshKind {
+        ProcessRefreshKind::nothing()
+            .with_memory()
+            .with_cpu()
+            .with_disk_usage()
+            .with_exe(UpdateKind::OnlyIfNotSet)
+            .with_cmd(UpdateKind::OnlyIfNotSet)
+    }
+
+    /// Returns the PIDs of the running processes.
+    ///
+    /// On Linux, `/proc` is listed directly since it is much cheaper than refreshing every
+    /// process. Threads are not included.
+    fn list_pids(&self) -> HashSet<Pid> {
+        match fs::read_dir("/proc") {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse().ok())
+                .collect(),
+            Err(_) => self.system.processes().keys().copied().collect(),
+        }
+    }
+
+    /// Compares the running processes with the previous refresh and logs the spawned and
+    /// exited ones.
+    fn refresh_process_events(&mut self) {
+        let pids = self.list_pids();
+        self.record_process_events(pids);
+    }
+
+    /// Logs the processes that were spawned and that exited since the given PIDs were last
+    /// listed.
+    fn record_process_events(&mut self, pids: HashSet<Pid>) {
+        let is_first_refresh = self.known_pids.is_empty();
+        let spawned = pids
+            .difference(&self.known_pids)
+            .copied()
+            .collect::<Vec<_>>();
+        // While paused, the spawned processes are read apart so that the process table stays
+        // frozen.
+        let mut paused_system = System::new();
+        let system = if self.paused {
+            &mut paused_system
+        } else {
+            &mut self.system
+        };
+        if !spawned.is_empty() {
+            // The CPU usage is left out since it would be computed over a single frame.
+            system.refresh_processes_specifics(
+                ProcessesToUpdate::Some(&spawned),
+                false,
+                Self::process_refresh_kind().without_cpu(),
+            );
+        }
+
+        let now = SystemTime::now();
+        let spawned = spawned
+            .into_iter()
+            .filter_map(|pid| {
+                let process = system.process(pid)?;
+                let summary = ProcessSummary {
+                    name: process.name().to_string_lossy().to_string(),
+                    parent: process.parent(),
+                    // Processes that are seen spawning get a more precise start time than the
+                    // one reported in seconds.
+                    start_time: if is_first_refresh {
+                        UNIX_EPOCH + Duration::from_secs(process.start_time())
+                    } else {
+                        now
+                    },
+                    memory: process.memory(),
+                    peak_cpu_usage: None,
+                    peak_memory: process.memory(),
+                    container: containers::read_container(pid),
+                    cgroup: cgroups::process_cgroup(pid),
+                };
+                Some((pid, summary))
+            })
+            .collect::<Vec<_>>();
+
+        let mut events = Vec::new();
+        for (pid, summary) in spawned {
+            if !is_first_refresh {
+                events.push(ProcessEvent {
+                    kind: ProcessEventKind::Spawned,
+                    time: now,
+                    pid,
+                    summary: summary.clone(),
+                });
+            }
+            self.process_summaries.insert(pid, summary);
+            // The PID may be reused while the history of its previous process is kept.
+            self.process_data.remove(&pid);
+            self.refresh_command_usage(pid, 0.0);
+        }
+        for pid in self.known_pids.difference(&pids) {
+            if let Some(command) = &mut self.command {
+                let reaped_in_tree = self
+                    .process_summaries
+                    .get(pid)
+                    .and_then(|summary| summary.parent)
+                    .is_some_and(|parent| {
+                        pids.contains(&parent) && command.usage.contains_key(&parent)
+                    });
+                if let Some(usage) = command.usage.get_mut(pid) {
+                    usage.reaped_in_tree = reaped_in_tree;
+                }
+            }
+            if let Some(summary) = self.process_summaries.remove(pid) {
+                events.push(ProcessEvent {
+                    kind: ProcessEventKind::Exited,
+                    time: now,
+                    pid: *pid,
+                    summary,
+                });
+            }
+        }
+        self.known_pids = pids;
+
+        self.process_events.extend(events);
+        let overflow = self.process_events.len().saturating_sub(EVENT_LOG_LEN);
+        self.process_events.drain(..overflow);
+    }
+
+    /// Record a sample for each process and forget the processes that have been gone for a
+    /// while.
+    fn refresh_process_data(&mut self, frame_count: usize) {
+        let now = Instant::now();
+        let elapsed = self
+            .process_refresh_time
+            .replace(now)
+            .map(|time| now.duration_since(time).as_secs_f64())
+            .unwrap_or_default();
+        self.process_refresh_interval = Duration::from_secs_f64(elapsed);
+
+        let processes = self.system.processes();
+        self.process_data.retain(|pid, data| {
+            processes.contains_key(pid)
+                || data.last().is_some_and(|last| {
+                    now.duration_since(last.time) < EXITED_PROCESS_HISTORY_DURATION
+                })
+        });
+        for (pid, process) in processes {
+            let data = self.process_data.entry(*pid).or_default();
+            let cpu_measured = !data.is_empty();
+            let disk_usage = process.disk_usage();
+            let io_bytes = disk_usage.read_bytes + disk_usage.written_bytes;
+            data.push(ProcessData {
+                cpu_usage: process.cpu_usage() as f64,
+                memory: process.memory(),
+                // The main thread is not listed in the tasks.
+                threads: process.tasks().map_or(1, |tasks| tasks.len() + 1),
+                io_rate: if elapsed > 0.0 {
+                    io_bytes as f64 / elapsed
+                } else {
+                    0.0
+                },
+                time: now,
+                point: frame_count as f64,
+            });
+            if data.len() > PROCESS_HISTORY_LEN {
+                data.remove(0);
+            }
+            if let Some(summary) = self.process_summaries.get_mut(pid) {
+                // Zombies have already freed their memory, keep what they used while alive.
+                if process.status() != ProcessStatus::Zombie {
+                    summary.memory = process.memory();
+                }
+                if cpu_measured {
+                    let cpu_usage = process.cpu_usage() as f64;
+                    summary.peak_cpu_usage = Some(
+                        summary
+                            .peak_cpu_usage
+                            .map_or(cpu_usage, |peak| peak.max(cpu_usage)),
+                    );
+                }
+                summary.peak_memory = summary.peak_memory.max(process.memory());
+            }
+        }
+
+        self.refresh_usage(now, elapsed);
+
+        if self.command.is_some() {
+            let pids = self.process_summaries.keys().copied().collect::<Vec<_>>();
+            let mut memory = 0;
+            for pid in pids {
+                if self.refresh_command_usage(pid, elapsed) {
+                    memory += self.system.process(pid).map_or(0, |p| p.memory());
+                }
+            }
+            if let Some(command) = &mut self.command {
+                command.peak_memory = command.peak_memory.max(memory);
+            }
+        }
+
+        if self
+            .memory_trend_time
+            .is_none_or(|time| now.duration_since(time) >= LEAK_SAMPLE_INTERVAL)
+        {
+            self.memory_trend_time = Some(now);
+            self.refresh_leak_suspects(now);
+        }
+    }
+
+    /// Accumulates the resource usage of the given process if it belongs to the tree of the
+    /// launched command.
+    ///
+    /// Returns whether the process belongs to the tree.
+    fn refresh_command_usage(&mut self, pid: Pid, elapsed: f64) -> bool {
+        let Some(root) = self.command.as_ref().map(|command| command.pid) else {
+            return false;
+        };
+        if !self.is_descendant(pid, root) {
+            return false;
+        }
+        let (Some(process), Some(command)) = (self.system.process(pid), &mut self.command) else {
+            return false;
+        };
+        let disk_usage = process.disk_usage();
+        let usage = command.usage.entry(pid).or_default();
+        usage.name = process.name().to_string_lossy().to_string();
+        usage.cpu_time += process.cpu_usage() as f64 / 100.0 * elapsed;
+        usage.peak_memory = usage.peak_memory.max(process.memory());
+        usage.read_bytes = disk_usage.total_read_bytes;
+        usage.written_bytes = disk_usage.total_written_bytes;
+        true
+    }
+
+    /// Returns whether the given process is the root process or one of its descendants.
+    fn is_descendant(&self, pid: Pid, root: Pid) -> bool {
+        let mut current = Some(pid);
+        // Guard against cycles since the parents are read at different times.
+        for _ in 0..self.system.processes().len() {
+            match current {
+                Some(pid) if pid == root => return true,
+                Some(pid) => current = self.system.process(pid).and_then(|p| p.parent()),
+                None => return false,
+            }
+        }
+        false
+    }
+
+    /// Accumulates the resource usage of each process since the previous refresh.
+    fn refresh_usage(&mut self, now: Instant, elapsed: f64) {
+        if self
+            .usage_buckets
+            .last()
+            .is_none_or(|bucket| now.duration_since(bucket.start) >= USAGE_BUCKET_DURATION)
+        {
+            self.usage_buckets.push(UsageBucket {
+                start: now,
+                usage: HashMap::new(),
+            });
+            let overflow = self.usage_buckets.len().saturating_sub(USAGE_BUCKET_LEN);
+            self.usage_buckets.drain(..overflow);
+        }
+        let Some(bucket) = self.usage_buckets.last_mut() else {
+            return;
+        };
+        self.ranking = None;
+
+        // Threads are accounted for in their process.
+        for (pid, summary) in &self.process_summaries {
+            let Some(process) = self.system.process(*pid) else {
+                continue;
+            };
+            let usage = bucket.usage.entry(*pid).or_default();
+            usage.name.clone_from(&summary.name);
+            usage.cpu_time += process.cpu_usage() as f64 / 100.0 * elapsed;
+            usage.memory_total += process.memory() as f64;
+            usage.samples += 1;
+            usage.peak_memory = usage.peak_memory.max(process.memory());
+        }
+    }
+
+    /// Samples the resident memory of each process and flags the ones that keep growing.
+    fn refresh_leak_suspects(&mut self, now: Instant) {
+        // Threads share the memory of their process so only processes are tracked.
+        self.memory_trends
+            .retain(|pid, _| self.process_summaries.contains_key(pid));
+        for pid in self.process_summaries.keys() {
+            let Some(process) = self.system.process(*pid) else {
+                continue;
+            };
+            let samples = self.memory_trends.entry(*pid).or_default();
+            samples.push(MemorySample {
+                time: now,
+                memory: process.memory(),
+            });
+            if samples.len() > LEAK_SAMPLE_LEN {
+                samples.remove(0);
+            }
+        }
+
+        self.leak_suspects = self
+            .memory_trends
+            .iter()
+            .filter_map(|(pid, samples)| {
+                let (first, last) = (samples.first()?, samples.last()?);
+                if samples.len() < LEAK_MIN_SAMPLES || last.memory <= first.memory {
+                    return None;
+                }
+                let growing = samples
+                    .windows(2)
+                    .filter(|pair| pair[1].memory >= pair[0].memory)
+                    .count();
+                if (growing as f64) < (samples.len() - 1) as f64 * LEAK_MIN_MONOTONICITY {
+                    return None;
+                }
+                let points = Self::memory_trend_points(samples);
+                let (slope, intercept) = linear_fit(&points);
+                let growth_rate = slope * 3600.0;
+                (growth_rate >= LEAK_MIN_GROWTH_RATE).then_some(LeakSuspect {
+                    pid: *pid,
+                    growth_rate,
+                    intercept,
+                })
+            })
+            .collect();
+        self.leak_suspects
+            .sort_by(|a, b| b.growth_rate.total_cmp(&a.growth_rate));
+    }
+
+    /// Returns the memory samples as seconds since the first sample and bytes.
+    fn memory_trend_points(samples: &[MemorySample]) -> Vec<(f64, f64)> {
+        let start = samples.first().map(|v| v.time);
+        samples
+            .iter()
+            .map(|v| {
+                let time = start.map_or(0.0, |start| v.time.duration_since(start).as_secs_f64());
+                (time, v.memory as f64)
+            })
+            .collect()
+    }
+
+    /// Renders the user interface.
+    fn render(&mut self, frame: &mut Frame) {
+        let [header_area, main_area] = Layout::vertical([Length(1), Min(0)]).areas(frame.area());
+
+        self.render_header(frame, header_area);
+        match self.screen {
+            Screen::Dashboard => self.render_dashboard(frame, main_area),
+            Screen::Events => self.render_events(frame, main_area),
+            Screen::Suspects => self.render_suspects(frame, main_area),
+            Screen::Ranking => self.render_ranking(frame, main_area),
+            Screen::Sockets => self.render_sockets(frame, main_area),
+            Screen::Interfaces => self.render_interfaces(frame, main_area),
+            Screen::Explorer => self.render_explorer(frame, main_area),
+            Screen::Pressure => self.render_pressure(frame, main_area),
+            Screen::Cgroups => self.render_cgroups(frame, main_area),
+            Screen::Inventory => self.render_inventory(frame, main_area),
+            Screen::Oom => self.render_oom_events(frame, main_area),
+        }
+        if let Some(pid) = self.inspected_pid {
+            self.render_process_detail(frame, main_area, pid);
+        }
+        self.render_oom_banner(frame, header_area);
+    }
+
+    /// Renders the dashboard.
+    ///
+    /// Split the area into 6 parts: cpu, disk, memory, network, top processes, and process.
     ///
     /// The resulting layout is as follows:
     ///
     /// ```
-    /// ──────────────────────────Header──────────────────────────
     // ┌CPU───────────────────────────────────────────────────────┐
     // │                                                          │
     // │                                                          │
@@ -167,36 +2018,130 @@ impl App {
     // │                ││                                        │
     // │                ││                                        │
     // └────────────────┘└────────────────────────────────────────┘
-    // ┌Network─────────────────────┐┌Processes───────────────────┐
-    // │                            ││                            │
-    // │                            ││                            │
-    // │                            ││                            │
-    // └────────────────────────────┘└────────────────────────────┘
+    // ┌Network──────┐┌Top──────────┐┌Processes───────────────────┐
+    // │             ││             ││                            │
+    // │             ││             ││                            │
+    // │             ││             ││                            │
+    // └─────────────┘└─────────────┘└────────────────────────────┘
     // ```
-    fn render(&mut self, frame: &mut Frame) {
-        let [header_area, main_area] = Layout::vertical([Length(1), Min(0)]).areas(frame.area());
-
+    fn render_dashboard(&mut self, frame: &mut Frame, area: Rect) {
         let [cpu_area, middle, bottom] =
-            Layout::vertical([Percentage(25), Fill(1), Fill(1)]).areas(main_area);
+            Layout::vertical([Percentage(25), Fill(1), Fill(1)]).areas(area);
 
         let [disk_area, memory_area] = Layout::horizontal([Percentage(30), Fill(1)]).areas(middle);
 
-        let [network_area, process_area] = Layout::horizontal([Fill(1); 2]).areas(bottom);
+        let [network_area, top_area, process_area] =
+            Layout::horizontal([Fill(1), Fill(1), Fill(2)]).areas(bottom);
 
-        self.render_header(frame, header_area);
         self.render_cpu(frame, cpu_area);
         self.render_disks(frame, disk_area);
         self.render_memory(frame, memory_area);
         self.render_networks(frame, network_area);
+        self.render_top_processes(frame, top_area);
         self.render_processes(frame, process_area);
         if self.searching {
-            self.render_search(frame, process_area);
+            match self.focus {
+                Focus::Processes | Focus::Disks | Focus::Memory => {
+                    Self::render_search(frame, process_area, &self.textarea)
+                }
+                Focus::Network => Self::render_search(frame, network_area, &self.network_filter),
+            }
         }
     }
 
     fn render_header(&self, frame: &mut Frame, area: Rect) {
+        let title = match self.scope {
+            Some(pid) => match self.system.process(pid) {
+                Some(process) => format!(
+                    "Ratatop │ {} ({pid}) up {}",
+                    process.name().to_string_lossy(),
+                    format_duration(Duration::from_secs(process.run_time()))
+                ),
+                None => format!("Ratatop │ {pid} (exited)"),
+            },
+            None => "Ratatop".to_string(),
+        };
+
+        // Threads are listed along with the processes, which count as their main thread.
+        let count_states = |processes: &mut dyn Iterator<Item = &Process>| {
+            let (mut running, mut sleeping, mut zombies) = (0, 0, 0);
+            for process in processes {
+                match process.status() {
+                    ProcessStatus::Run => running += 1,
+                    ProcessStatus::Sleep | ProcessStatus::Idle => sleeping += 1,
+                    ProcessStatus::Zombie => zombies += 1,
+                    _ => {}
+                }
+            }
+            format!("{running} running, {sleeping} sleeping, {zombies} zombie")
+        };
+        let process_states = count_states(
+            &mut self
+                .process_summaries
+                .keys()
+                .filter_map(|pid| self.system.process(*pid)),
+        );
+        let thread_states = count_states(&mut self.system.processes().values());
+        let now = SystemTime::now();
+        let uptime = now
+            .duration_since(UNIX_EPOCH + Duration::from_secs(self.host.boot_time))
+            .unwrap_or_default();
+        let cpus = self.system.cpus();
+        let cpu_model = cpus.first().map_or("", |cpu| cpu.brand().trim());
+        let state = if self.paused {
+            Span::from("❚❚ paused").fg(tailwind::ORANGE.c400)
+        } else {
+            Span::from(format!(
+                "● live {:.1}s",
+                self.process_refresh_interval.as_secs_f64()
+            ))
+        };
+
+        // The header is shown in this order and, when it does not fit, the segments are
+        // dropped from the least important one.
+        let mut segments = vec![
+            (0, Span::from(title)),
+            (3, Span::from(self.host.name.clone())),
+            (
+                7,
+                Span::from(format!(
+                    "{} │ kernel {}",
+                    self.host.os_version, self.host.kernel_version
+                )),
+            ),
+            (4, Span::from(format!("up {}", format_duration(uptime)))),
+            (6, Span::from(format!("{} × {cpu_model}", cpus.len()))),
+            (
+                5,
+                Span::from(format!(
+                    "{} processes: {process_states} │ {} threads: {thread_states}",
+                    self.process_summaries.len(),
+                    self.system.processes().len()
+                )),
+            ),
+            (2, Span::from(format_time(now))),
+            (1, state),
+        ];
+        let width = |segments: &[(u8, Span)]| {
+            let separators = 3 * segments.len().saturating_sub(1);
+            separators + segments.iter().map(|(_, span)| span.width()).sum::<usize>()
+        };
+        while segments.len() > 1 && width(&segments) > area.width as usize {
+            let least_important = (0..segments.len())
+                .max_by_key(|index| segments[*index].0)
+                .unwrap();
+            segments.remove(least_important);
+        }
+        let mut spans = Vec::new();
+        for (index, (_, span)) in segments.into_iter().enumerate() {
+            if index > 0 {
+                spans.push(Span::from(" │ "));
+            }
+            spans.push(span);
+        }
+
         frame.render_widget(
-            Line::from("Ratatop")
+            Line::from(spans)
                 .alignment(Alignment::Center)
                 .fg(tailwind::BLUE.c200)
                 .bg(tailwind::GRAY.c800)
@@ -214,12 +2159,27 @@ impl App {
             .map(|v| (v.point, v.usage))
             .collect::<Vec<_>>();
 
-        let datasets = vec![Dataset::default()
+        let mut datasets = vec![Dataset::default()
             .marker(Marker::Braille)
             .graph_type(GraphType::Line)
             .style(tailwind::GREEN.c400)
             .data(&data)];
 
+        let scope_data = self
+            .cpu_data
+            .iter()
+            .map(|v| (v.point, v.scope_usage))
+            .collect::<Vec<_>>();
+        if self.scope.is_some() {
+            datasets.push(
+                Dataset::default()
+                    .marker(Marker::Braille)
+                    .graph_type(GraphType::Line)
+                    .style(tailwind::PURPLE.c400)
+                    .data(&scope_data),
+            );
+        }
+
         let current_percentage = self.cpu_data.last().map(|v| v.usage).unwrap_or_default();
         let current_percentage_line =
             format!("{:.2}%", current_percentage).fg(match current_percentage {
@@ -228,8 +2188,18 @@ impl App {
                 _ => tailwind::RED.c600,
             });
 
+        let mut title = Line::from(current_percentage_line);
+        if self.scope.is_some() {
+            let scope_percentage = self.cpu_data.last().map(|v| v.scope_usage);
+            title.push_span(" │ ".fg(tailwind::GRAY.c600));
+            title.push_span(
+                format!("Tree {:.2}%", scope_percentage.unwrap_or_default())
+                    .fg(tailwind::PURPLE.c400),
+            );
+        }
+
         let x_axis = Axis::default()
-            .title(current_percentage_line)
+            .title(title)
             .bounds([0.0, self.cpu_data.len() as f64]);
 
         let y_axis = Axis::default()
@@ -252,39 +2222,79 @@ impl App {
 
     /// Renders a bar chart of disk usage.
     fn render_disks(&self, frame: &mut Frame, area: Rect) {
-        let bars = self
-            .disk_data
-            .iter()
-            .map(|disk| {
-                let name = disk.name.rsplit('/').next().unwrap().to_string();
-                let percent = (disk.available as f64 / disk.total as f64 * 100.0) as u64;
-                let style = match percent {
-                    0..=50 => tailwind::GREEN.c400,
-                    51..=80 => tailwind::YELLOW.c300,
-                    _ => tailwind::RED.c600,
-                };
-                Bar::default()
-                    .label(name.fg(tailwind::BLUE.c100).into())
-                    .value(percent)
-                    .style(style)
-            })
-            .collect::<Vec<_>>();
+        let color = |percent: f64| match percent {
+            ..=50.0 => tailwind::GREEN.c400,
+            ..=80.0 => tailwind::YELLOW.c300,
+            _ => tailwind::RED.c600,
+        };
 
-        let data = BarGroup::default().bars(&bars);
-        let bar_chart = BarChart::default()
-            .block(Self::create_pane("Disks"))
+        let mut block = Self::create_pane("Disks │ used");
+        if self.focus == Focus::Disks {
+            block = block.border_style(tailwind::BLUE.c400);
+        }
+        let mut bar_chart = BarChart::default()
+            .block(block)
             .style(Style::new().bg(tailwind::GRAY.c900))
             .direction(Direction::Horizontal)
-            .data(data)
-            .bar_gap(1)
+            .bar_gap(0)
+            .group_gap(1)
             .bar_width(1)
             .bar_style(Style::new().on_black());
 
+        for (index, disk) in self.disk_data.iter().enumerate() {
+            let mut name = disk
+                .name
+                .rsplit('/')
+                .next()
+                .unwrap()
+                .to_string()
+                .fg(tailwind::BLUE.c100);
+            if self.focus == Focus::Disks && index == self.selected_disk {
+                name = name.fg(tailwind::BLUE.c200).bg(tailwind::GRAY.c800);
+            }
+            let used_percent = disk.used_percent();
+            let inode_percent = disk.inode_percent();
+            // The disk is alerted when either the space or the inodes run out.
+            let mut style = color(used_percent.max(inode_percent.unwrap_or_default()));
+
+            let mut text = format!("{used_percent:.0}%");
+            for flag in disk.flags() {
+                text.push_str(&format!(" {flag}"));
+            }
+            if let Some(full_in) = disk.full_in {
+                text.push_str(&format!(" full in ~{}", format_duration(full_in)));
+                if full_in < self.disk_horizon {
+                    style = tailwind::RED.c600;
+                }
+            }
+            let mut bars = vec![Bar::default()
+                .label(name.into())
+                .value(used_percent as u64)
+                .text_value(text)
+                .style(style)];
+            if let Some(inode_percent) = inode_percent {
+                bars.push(
+                    Bar::default()
+                        .label("inodes".fg(tailwind::GRAY.c400).into())
+                        .value(inode_percent as u64)
+                        .text_value(format!("{inode_percent:.0}%"))
+                        .style(style),
+                );
+            }
+            bar_chart = bar_chart.data(BarGroup::default().bars(&bars));
+        }
+
         frame.render_widget(bar_chart, area);
     }
 
     /// Renders a chart of memory usage.
     fn render_memory(&self, frame: &mut Frame, area: Rect) {
+        match self.memory_view {
+            MemoryView::Chart => {}
+            MemoryView::Activity => return self.render_memory_activity(frame, area),
+            _ => return self.render_memory_treemap(frame, area),
+        }
+
         let current_percentage =
             self.system.used_memory() as f64 / self.system.total_memory() as f64 * 100.0;
 
@@ -302,13 +2312,32 @@ impl App {
             .map(|v| (v.point, v.usage))
             .collect::<Vec<_>>();
 
-        let datasets = vec![Dataset::default()
+        let mut datasets = vec![Dataset::default()
             .name(current_percentage_line)
             .marker(Marker::Bar)
             .graph_type(GraphType::Line)
             .style(tailwind::BLUE.c400)
             .data(&data)];
 
+        let scope_data = self
+            .memory_data
+            .iter()
+            .map(|v| (v.point, v.scope_usage))
+            .collect::<Vec<_>>();
+        if self.scope.is_some() {
+            let scope_percentage = self.memory_data.last().map_or(0.0, |v| {
+                v.scope_usage / self.system.total_memory() as f64 * 100.0
+            });
+            datasets.push(
+                Dataset::default()
+                    .name(format!("Tree {scope_percentage:.2}%").fg(tailwind::PURPLE.c400))
+                    .marker(Marker::Bar)
+                    .graph_type(GraphType::Line)
+                    .style(tailwind::PURPLE.c400)
+                    .data(&scope_data),
+            );
+        }
+
         let x_axis = Axis::default().bounds([0.0, self.memory_data.len() as f64]);
         let y_axis = Axis::default().bounds([0.0, self.system.total_memory() as f64]);
 
@@ -321,14 +2350,286 @@ impl App {
         frame.render_widget(chart, area);
     }
 
+    /// Renders the paging, swapping and reclaim rates next to the major page faults.
+    ///
+    /// Swapping while memory is reclaimed tells a thrashing machine from one with a full
+    /// but idle swap.
+    fn render_memory_activity(&self, frame: &mut Frame, area: Rect) {
+        let [paging_area, faults_area] = Layout::horizontal([Fill(2), Fill(1)]).areas(area);
+        let points = |value: fn(&vmstat::Rates) -> f64| {
+            self.vmstat_data
+                .iter()
+                .map(|v| (v.point, value(&v.rates)))
+                .collect::<Vec<_>>()
+        };
+        let bounds = match (self.vmstat_data.first(), self.vmstat_data.last()) {
+            (Some(first), Some(last)) => [first.point, last.point.max(first.point + 1.0)],
+            _ => [0.0, 1.0],
+        };
+        let last = self.vmstat_data.last().map(|v| v.rates).unwrap_or_default();
+
+        let series = [
+            ("page in", tailwind::BLUE.c400, points(|v| v.page_in)),
+            ("page out", tailwind::CYAN.c400, points(|v| v.page_out)),
+            ("swap in", tailwind::ORANGE.c400, points(|v| v.swap_in)),
+            ("swap out", tailwind::RED.c400, points(|v| v.swap_out)),
+            ("reclaim", tailwind::PURPLE.c400, points(|v| v.reclaimed)),
+        ];
+        let datasets = series
+            .iter()
+            .map(|(name, color, points)| {
+                Dataset::default()
+                    .name(*name)
+                    .marker(Marker::Braille)
+                    .graph_type(GraphType::Line)
+                    .style(*color)
+                    .data(points)
+            })
+            .collect::<Vec<_>>();
+        let max = series
+            .iter()
+            .flat_map(|(_, _, points)| points)
+            .map(|(_, value)| *value)
+            .fold(1.0, f64::max);
+        let title = format!(
+            "Paging │ in {}/s out {}/s │ swap in {}/s out {}/s",
+            format_bytes(last.page_in as u64),
+            format_bytes(last.page_out as u64),
+            format_bytes(last.swap_in as u64),
+            format_bytes(last.swap_out as u64),
+        );
+        let chart = Chart::new(datasets)
+            .block(Self::create_pane(&title))
+            .style(Style::new().bg(tailwind::GRAY.c900))
+            .x_axis(Axis::default().bounds(bounds))
+            .y_axis(
+                Axis::default()
+                    .bounds([0.0, max])
+                    .labels(vec![
+                        "0".to_string(),
+                        format!("{}/s", format_bytes(max as u64)),
+                    ])
+                    .style(tailwind::GRAY.c600),
+            )
+            .hidden_legend_constraints((Ratio(1, 2), Ratio(3, 4)));
+        frame.render_widget(chart, paging_area);
+
+        let faults = points(|v| v.major_faults);
+        let max = faults.iter().map(|(_, value)| *value).fold(1.0, f64::max);
+        let datasets = vec![Dataset::default()
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(tailwind::YELLOW.c300)
+            .data(&faults)];
+        let title = format!("Major faults │ {:.0}/s", last.major_faults);
+        let chart = Chart::new(datasets)
+            .block(Self::create_pane(&title))
+            .style(Style::new().bg(tailwind::GRAY.c900))
+            .x_axis(Axis::default().bounds(bounds))
+            .y_axis(
+                Axis::default()
+                    .bounds([0.0, max])
+                    .labels(vec!["0".to_string(), format!("{max:.0}/s")])
+                    .style(tailwind::GRAY.c600),
+            );
+        frame.render_widget(chart, faults_area);
+    }
+
+    /// Returns the largest processes by resident memory, grouped according to the view.
+    fn memory_groups(&self) -> Vec<MemoryGroup> {
+        // Threads share the memory of their process so only processes are shown.
+        let mut processes = self
+            .process_summaries
+            .keys()
+            .filter(|pid| {
+                self.scope
+                    .is_none_or(|root| self.is_descendant(**pid, root))
+            })
+            .filter_map(|pid| self.system.process(*pid))
+            .map(|process| {
+                let name = process.name().to_string_lossy().to_string();
+                (process.pid(), name, process.memory())
+            })
+            .collect::<Vec<_>>();
+        processes.sort_by_key(|(pid, _, memory)| (std::cmp::Reverse(*memory), *pid));
+        processes.truncate(TREEMAP_LEN);
+
+        let mut groups = Vec::<MemoryGroup>::new();
+        for process in processes {
+            let name = match self.memory_view {
+                MemoryView::Chart | MemoryView::Activity | MemoryView::Processes => String::new(),
+                MemoryView::Users => self
+                    .system
+                    .process(process.0)
+                    .and_then(|process| process.user_id())
+                    .map(|uid| match self.users.get_user_by_id(uid) {
+                        Some(user) => user.name().to_string(),
+                        None => uid.to_string(),
+                    })
+                    .unwrap_or_else(|| "unknown".to_string()),
+                MemoryView::Parents => self
+                    .system
+                    .process(process.0)
+                    .and_then(|process| process.parent())
+                    .and_then(|parent| self.system.process(parent))
+                    .map(|parent| format!("{} ({})", parent.name().to_string_lossy(), parent.pid()))
+                    .unwrap_or_else(|| "none".to_string()),
+            };
+            match groups.iter_mut().find(|group| group.name == name) {
+                Some(group) => {
+                    group.memory += process.2;
+                    group.processes.push(process);
+                }
+                None => groups.push(MemoryGroup {
+                    name,
+                    memory: process.2,
+                    processes: vec![process],
+                }),
+            }
+        }
+        groups.sort_by_key(|group| std::cmp::Reverse(group.memory));
+        groups
+    }
+
+    /// Renders a squarified treemap of the resident memory of the largest processes.
+    ///
+    /// Processes of a group share a color and the largest one is labelled with the group.
+    fn render_memory_treemap(&self, frame: &mut Frame, area: Rect) {
+        let title = match self.memory_view {
+            MemoryView::Chart | MemoryView::Activity | MemoryView::Processes => {
+                "Memory │ by process"
+            }
+            MemoryView::Users => "Memory │ by user",
+            MemoryView::Parents => "Memory │ by parent",
+        };
+        let mut block = Self::create_pane(title);
+        if self.focus == Focus::Memory {
+            block = block.border_style(tailwind::BLUE.c400);
+        }
+        let inner_area = block.inner(area);
+        frame.render_widget(block, area);
+
+        let palettes = [
+            tailwind::BLUE,
+            tailwind::GREEN,
+            tailwind::PURPLE,
+            tailwind::ORANGE,
+            tailwind::CYAN,
+            tailwind::PINK,
+            tailwind::YELLOW,
+            tailwind::TEAL,
+        ];
+        let grouped = !matches!(
+            self.memory_view,
+            MemoryView::Chart | MemoryView::Activity | MemoryView::Processes
+        );
+
+        let groups = self.memory_groups();
+        let group_memory = groups
+            .iter()
+            .map(|group| group.memory as f64)
+            .collect::<Vec<_>>();
+        let group_areas = treemap::squarify(&group_memory, inner_area);
+        let mut index = 0;
+        for (group_index, (group, group_area)) in groups.iter().zip(group_areas).enumerate() {
+            let memory = group
+                .processes
+                .iter()
+                .map(|(_, _, memory)| *memory as f64)
+                .collect::<Vec<_>>();
+            let cells = treemap::squarify(&memory, group_area);
+            for (cell_index, ((_, name, memory), cell)) in
+                group.processes.iter().zip(cells).enumerate()
+            {
+                // Neighbouring cells of a group alternate shades to stay apart.
+                let palette = &palettes[if grouped { group_index } else { index } % palettes.len()];
+                let mut style = Style::new()
+                    .bg(if cell_index % 2 == 0 {
+                        palette.c800
+                    } else {
+                        palette.c700
+                    })
+                    .fg(tailwind::GRAY.c100);
+                if self.focus == Focus::Memory && index == self.selected_treemap_cell {
+                    style = style.bg(tailwind::BLUE.c200).fg(tailwind::GRAY.c900);
+                }
+                index += 1;
+                if cell.is_empty() {
+                    continue;
+                }
+                frame.buffer_mut().set_style(cell, style);
+
+                let mut lines = vec![Line::from(name.as_str()), Line::from(format_bytes(*memory))];
+                if grouped && cell_index == 0 {
+                    lines.insert(0, Line::from(group.name.as_str()).bold());
+                }
+                // Keep a space between the labels of neighbouring cells.
+                let label_area = if cell.width > 3 {
+                    Rect {
+                        width: cell.width - 1,
+                        ..cell
+                    }
+                } else {
+                    cell
+                };
+                for (line, row) in lines.into_iter().zip(label_area.rows()) {
+                    frame.render_widget(line.style(style), row);
+                }
+            }
+        }
+    }
+
+    /// Returns the PID of the selected cell of the memory treemap.
+    fn selected_treemap_pid(&self) -> Option<Pid> {
+        self.memory_groups()
+            .into_iter()
+            .flat_map(|group| group.processes)
+            .nth(self.selected_treemap_cell)
+            .map(|(pid, _, _)| pid)
+    }
+
     /// Renders a sparkline for each network interface.
-    fn render_networks(&self, frame: &mut Frame, area: Rect) {
-        let block = Self::create_pane("Network");
+    fn render_networks(&mut self, frame: &mut Frame, area: Rect) {
+        let mut network_data = self
+            .network_data
+            .iter()
+            .filter(|(name, _)| self.is_network_shown(name))
+            .collect::<Vec<_>>();
+        match self.network_sort {
+            NetworkSort::Name => network_data.sort_by_key(|(name, _)| *name),
+            // The rates are averaged between the refreshes of the interfaces so that the order
+            // does not change on every frame.
+            NetworkSort::Throughput => network_data.sort_by(|(name1, _), (name2, _)| {
+                let throughput = |name: &str| {
+                    self.interfaces
+                        .get(name)
+                        .map_or(0.0, |details| details.receive_rate + details.transmit_rate)
+                };
+                throughput(name2)
+                    .total_cmp(&throughput(name1))
+                    .then(name1.cmp(name2))
+            }),
+        }
+
+        let title = self.networks_title(network_data.len());
+        let mut block = Self::create_pane(&title);
+        if self.focus == Focus::Network {
+            block = block.border_style(tailwind::BLUE.c400);
+        }
         let inner_block = block.inner(area);
         frame.render_widget(block, area);
 
-        let mut network_data = self.network_data.iter().collect::<Vec<_>>();
-        network_data.sort_by(|(name1, _), (name2, _)| name1.cmp(name2));
+        // Do not scroll past the last interface.
+        self.network_scroll = self.network_scroll.min(
+            network_data
+                .len()
+                .saturating_sub(inner_block.height as usize),
+        );
+        let network_data = network_data
+            .into_iter()
+            .skip(self.network_scroll)
+            .take(inner_block.height as usize)
+            .collect::<Vec<_>>();
 
         let longest_name = network_data
             .iter()
@@ -365,56 +2666,1700 @@ impl App {
         }
     }
 
-    /// Renders a table of processes.
-    fn render_processes(&mut self, frame: &mut Frame, area: Rect) {
-        let header = Row::new(vec!["Pid", "Cmd", "CPU%", "Mem%"]).style(tailwind::YELLOW.c200);
+    /// Returns the title of the network pane along with the number of shown interfaces.
+    fn networks_title(&self, shown: usize) -> String {
+        let mut title = format!("Network ({shown}/{})", self.network_data.len());
+        if self.network_sort == NetworkSort::Throughput {
+            title.push_str(" │ by throughput");
+        }
+        let hidden = [
+            (self.hide_loopback, "loopback"),
+            (self.hide_down, "down"),
+            (self.hide_virtual, "virtual"),
+        ]
+        .into_iter()
+        .filter_map(|(hide, name)| hide.then_some(name))
+        .collect::<Vec<_>>();
+        if !hidden.is_empty() {
+            title.push_str(&format!(" │ hiding {}", hidden.join(", ")));
+        }
+        let patterns = self.network_filter.lines().first().unwrap();
+        if !patterns.trim().is_empty() {
+            title.push_str(&format!(" │ {}", patterns.trim()));
+        }
+        title
+    }
 
-        let widths = [Length(10), Fill(2), Fill(1), Fill(1)];
-        let mut rows = Vec::new();
-        for (pid, process) in self.system.processes() {
-            let row = vec![
-                pid.to_string(),
-                process.name().to_string_lossy().to_string(),
-                format!("{:.2}", process.cpu_usage()),
-                format!(
+    /// Whether the given interface passes the toggles and name patterns of the network pane.
+    fn is_network_shown(&self, name: &str) -> bool {
+        let hidden = match self.interfaces.get(name).map(|details| &details.link) {
+            Some(link) => {
+                (self.hide_loopback && link.loopback)
+                    || (self.hide_down && link.operstate == "down")
+                    || (self.hide_virtual && link.virtual_device)
+            }
+            // Without its link, an interface cannot be told apart from the hidden ones.
+            None => self.hide_loopback || self.hide_down || self.hide_virtual,
+        };
+        !hidden && matches_patterns(name, self.network_filter.lines().first().unwrap())
+    }
+
+    /// Renders the top processes by the selected metric, styled like the disks pane.
+    ///
+    /// CPU and memory are colored by their share of the system, disk I/O and threads by
+    /// their share of the busiest process.
+    fn render_top_processes(&self, frame: &mut Frame, area: Rect) {
+        let mut processes = self
+            .process_summaries
+            .iter()
+            .filter(|(pid, _)| {
+                self.scope
+                    .is_none_or(|root| self.is_descendant(**pid, root))
+            })
+            .filter_map(|(pid, summary)| {
+                let data = self.process_data.get(pid)?.last()?;
+                let value = match self.top_metric {
+                    TopMetric::Cpu => self.cpu_percent(data.cpu_usage),
+                    TopMetric::Memory => {
+                        data.memory as f64 / self.system.total_memory() as f64 * 100.0
+                    }
+                    TopMetric::DiskIo => data.io_rate,
+                    TopMetric::Threads => data.threads as f64,
+                };
+                Some((summary.name.as_str(), value))
+            })
+            .collect::<Vec<_>>();
+        processes.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(b.0)));
+        // Each bar takes a line and a gap.
+        processes.truncate((area.height.saturating_sub(1) / 2) as usize);
+
+        let max = processes.first().map_or(0.0, |(_, value)| *value);
+        let bars = processes
+            .iter()
+            .map(|(name, value)| {
+                let (percent, text) = match self.top_metric {
+                    TopMetric::Cpu | TopMetric::Memory => (*value, format!("{value:.1}%")),
+                    TopMetric::DiskIo => (
+                        value / max.max(1.0) * 100.0,
+                        format!("{}/s", format_bytes(*value as u64)),
+                    ),
+                    TopMetric::Threads => (value / max.max(1.0) * 100.0, format!("{value}")),
+                };
+                let style = match percent {
+                    ..=50.0 => tailwind::GREEN.c400,
+                    ..=80.0 => tailwind::YELLOW.c300,
+                    _ => tailwind::RED.c600,
+                };
+                Bar::default()
+                    .label(name.fg(tailwind::BLUE.c100).into())
+                    .value(percent.round() as u64)
+                    .text_value(text)
+                    .style(style)
+            })
+            .collect::<Vec<_>>();
+
+        let title = format!("Top │ {}", self.top_metric);
+        let bar_chart = BarChart::default()
+            .block(Self::create_pane(&title))
+            .style(Style::new().bg(tailwind::GRAY.c900))
+            .direction(Direction::Horizontal)
+            .data(BarGroup::default().bars(&bars))
+            .max(100)
+            .bar_gap(1)
+            .bar_width(1)
+            .bar_style(Style::new().on_black());
+
+        frame.render_widget(bar_chart, area);
+    }
+
+    /// Renders a table of processes.
+    fn render_processes(&mut self, frame: &mut Frame, area: Rect) {
+        let cpu_header = match self.cpu_mode {
+            CpuMode::PerCore => "CPU% (core)",
+            CpuMode::Machine => "CPU% (all)",
+        };
+        let (header, widths) = if self.group_by_container {
+            (
+                vec!["Procs", "Container", cpu_header, "Mem%"],
+                vec![Length(10), Fill(2), Fill(1), Fill(1)],
+            )
+        } else {
+            let mut header = vec!["Pid", "Cmd", cpu_header, "Mem%", "Container"];
+            let mut widths = vec![Length(10), Fill(2), Fill(1), Fill(1), Length(20)];
+            if self.show_sparklines {
+                header.extend(["CPU~", "Mem~"]);
+                widths.extend([Length(SPARKLINE_LEN as u16); 2]);
+            }
+            (header, widths)
+        };
+        let header = Row::new(header).style(tailwind::YELLOW.c200);
+
+        // Rows of containers are only keyed by one of their processes.
+        let (highlights, suspects) = if self.group_by_container {
+            (HashMap::new(), HashSet::new())
+        } else {
+            (
+                self.recent_process_events()
+                    .map(|event| (event.pid, event.kind))
+                    .collect::<HashMap<_, _>>(),
+                self.leak_suspects
+                    .iter()
+                    .map(|suspect| suspect.pid)
+                    .collect::<HashSet<_>>(),
+            )
+        };
+
+        let process_rows = self.process_rows();
+        // The rows may have been reordered since the selection was requested.
+        let pending_index = self
+            .pending_selection
+            .take()
+            .and_then(|pid| process_rows.iter().position(|(row_pid, _)| *row_pid == pid));
+        if pending_index.is_some() {
+            self.table_state.select(pending_index);
+        }
+
+        // Stop scrolling once the end of the longest command is visible. The table leaves room
+        // for the borders and the highlight symbol, and a space between the columns.
+        let command_width = Layout::horizontal(widths.clone())
+            .flex(Flex::Legacy)
+            .spacing(1)
+            .split(Rect::new(0, 0, area.width.saturating_sub(4), 1))[1]
+            .width as usize;
+        let longest_command = process_rows
+            .iter()
+            .map(|(_, row)| row[1].chars().count())
+            .max()
+            .unwrap_or_default();
+        self.horizontal_scroll = self
+            .horizontal_scroll
+            .min(longest_command.saturating_sub(command_width));
+
+        let rows = process_rows
+            .into_iter()
+            .map(|(pid, mut row)| {
+                // Scroll the command column horizontally after filtering so that search
+                // matches the whole command line.
+                row[1] = row[1].chars().skip(self.horizontal_scroll).collect();
+                if suspects.contains(&pid) {
+                    row[0].push_str(" ↑");
+                }
+                let color = match highlights.get(&pid) {
+                    Some(ProcessEventKind::Spawned) => tailwind::GREEN.c400,
+                    Some(ProcessEventKind::Exited) => tailwind::RED.c400,
+                    None if suspects.contains(&pid) => tailwind::ORANGE.c400,
+                    None => tailwind::GRAY.c400,
+                };
+                Row::new(row).fg(color)
+            })
+            .collect::<Vec<_>>();
+
+        // The selection is lost while the table is empty, e.g. before a launched command shows up.
+        if self.table_state.selected().is_none() && !rows.is_empty() {
+            self.table_state.select(Some(0));
+        }
+
+        let title = self.processes_title();
+        let table = Table::new(rows, widths)
+            .header(header)
+            .style(tailwind::GRAY.c900)
+            .row_highlight_style(Style::new().bg(tailwind::GRAY.c800).fg(tailwind::BLUE.c200))
+            .highlight_symbol("> ")
+            .block(Self::create_pane(&title));
+
+        frame.render_stateful_widget(table, area, &mut self.table_state);
+    }
+
+    /// Returns the title of the process table.
+    ///
+    /// When the dashboard is focused on a process tree, its aggregated usage is shown.
+    fn processes_title(&self) -> String {
+        let (Some(root), Some(usage)) = (self.scope, self.scope_usage()) else {
+            let mut title = "Processes".to_string();
+            if let Some(path) = &self.cgroup_filter {
+                title.push_str(&format!(" │ cgroup {path}"));
+            }
+            if let Some(container) = &self.container_filter {
+                let name = container
+                    .as_ref()
+                    .map_or_else(|| "host".to_string(), Container::to_string);
+                title.push_str(&format!(" │ container {name}"));
+            }
+            if self.cgroup_filter.is_some() || self.container_filter.is_some() {
+                title.push_str(" (Esc to clear)");
+            }
+            return title;
+        };
+        let name = match &self.command {
+            Some(command) => command.command.clone(),
+            None => self
+                .process_summaries
+                .get(&root)
+                .map(|summary| summary.name.clone())
+                .unwrap_or_else(|| root.to_string()),
+        };
+        format!(
+            "Processes │ CPU {:.2}% │ Mem {} │ I/O {}/s │ {name}",
+            self.cpu_percent(usage.cpu_usage),
+            format_bytes(usage.memory),
+            format_bytes(usage.io_rate as u64)
+        )
+    }
+
+    /// Returns the CPU usage of a process, given per core, in the current accounting mode.
+    fn cpu_percent(&self, usage: f64) -> f64 {
+        match self.cpu_mode {
+            CpuMode::PerCore => usage,
+            CpuMode::Machine => usage / self.system.cpus().len().max(1) as f64,
+        }
+    }
+
+    /// Returns the aggregated usage of the process tree the dashboard is focused on.
+    fn scope_usage(&self) -> Option<ScopeUsage> {
+        let root = self.scope?;
+        let mut usage = ScopeUsage::default();
+        // Threads are accounted for in their process.
+        for pid in self.process_summaries.keys() {
+            if !self.is_descendant(*pid, root) {
+                continue;
+            }
+            if let Some(process) = self.system.process(*pid) {
+                usage.cpu_usage += process.cpu_usage() as f64;
+                usage.memory += process.memory();
+            }
+            usage.io_rate += self
+                .process_data
+                .get(pid)
+                .and_then(|data| data.last())
+                .map_or(0.0, |v| v.io_rate);
+        }
+        Some(usage)
+    }
+
+    /// Returns the filtered and sorted rows of the process table along with their PIDs.
+    ///
+    /// When grouped by container, each row is keyed by the largest process of the container.
+    fn process_rows(&self) -> Vec<(Pid, Vec<String>)> {
+        let mut rows = if self.group_by_container {
+            self.container_rows()
+        } else {
+            self.unsorted_process_rows()
+        };
+
+        // Conditions such as `cpu>50` are matched against their column and the rest of the
+        // text against any cell.
+        let (conditions, text) = self.textarea.lines().first().unwrap().split(' ').fold(
+            (Vec::new(), Vec::new()),
+            |(mut conditions, mut text), term| {
+                match FilterCondition::parse(term) {
+                    Some(condition) => conditions.push(condition),
+                    None => text.push(term),
+                }
+                (conditions, text)
+            },
+        );
+        let text = text.join(" ").to_lowercase();
+        rows.retain(|(_, row)| {
+            conditions.iter().all(|condition| condition.matches(row))
+                && row.iter().any(|cell| cell.to_lowercase().contains(&text))
+        });
+
+        // Ties are broken by PID so that the order does not change between frames.
+        rows.sort_by(|(a_pid, a), (b_pid, b)| {
+            a[2].parse::<f64>()
+                .unwrap_or_default()
+                .partial_cmp(&b[2].parse::<f64>().unwrap_or_default())
+                .unwrap()
+                .reverse()
+                .then(a_pid.cmp(b_pid))
+        });
+
+        rows
+    }
+
+    /// Returns a row for each process and thread, and for the processes that have just exited.
+    fn unsorted_process_rows(&self) -> Vec<(Pid, Vec<String>)> {
+        let mut rows = Vec::new();
+        for (pid, process) in self.system.processes() {
+            if !self.is_process_shown(*pid) {
+                continue;
+            }
+            let command = if self.show_command && !process.cmd().is_empty() {
+                process
+                    .cmd()
+                    .iter()
+                    .map(|arg| arg.to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            } else {
+                process.name().to_string_lossy().to_string()
+            };
+            let mut row = vec![
+                pid.to_string(),
+                command,
+                format!("{:.2}", self.cpu_percent(process.cpu_usage() as f64)),
+                format!(
                     "{:.2}",
                     process.memory() as f64 / self.system.total_memory() as f64 * 100.0
                 ),
+                self.process_container(*pid)
+                    .map(Container::to_string)
+                    .unwrap_or_default(),
             ];
-            rows.push(row);
+            if self.show_sparklines {
+                let data = self
+                    .process_data
+                    .get(pid)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                let data = &data[data.len().saturating_sub(SPARKLINE_LEN)..];
+                let cpu = data
+                    .iter()
+                    .map(|v| self.cpu_percent(v.cpu_usage))
+                    .collect::<Vec<_>>();
+                let memory = data.iter().map(|v| v.memory as f64).collect::<Vec<_>>();
+                // CPU usage is scaled to 100% while memory is scaled to its own peak so that the
+                // growth of small processes is still visible.
+                row.push(Self::create_sparkline(&cpu, 100.0));
+                row.push(Self::create_sparkline(&memory, 0.0));
+            }
+            rows.push((*pid, row));
+        }
+
+        // Keep showing the processes that have just exited.
+        for event in self.recent_process_events() {
+            if event.kind != ProcessEventKind::Exited
+                || self.system.processes().contains_key(&event.pid)
+            {
+                continue;
+            }
+            if self
+                .container_filter
+                .as_ref()
+                .is_some_and(|container| event.summary.container != *container)
+            {
+                continue;
+            }
+            if self.scope.is_some_and(|root| {
+                event.pid != root
+                    && !event
+                        .summary
+                        .parent
+                        .is_some_and(|parent| self.is_descendant(parent, root))
+            }) {
+                continue;
+            }
+            let mut row = vec![
+                event.pid.to_string(),
+                event.summary.name.clone(),
+                format!("{:.2}", 0.0),
+                format!(
+                    "{:.2}",
+                    event.summary.memory as f64 / self.system.total_memory() as f64 * 100.0
+                ),
+                event
+                    .summary
+                    .container
+                    .as_ref()
+                    .map(Container::to_string)
+                    .unwrap_or_default(),
+            ];
+            if self.show_sparklines {
+                row.extend([String::new(), String::new()]);
+            }
+            rows.push((event.pid, row));
+        }
+
+        rows
+    }
+
+    /// Returns a row for each container with the number of processes and their aggregated
+    /// usage. Processes outside of any container are grouped under `host`.
+    fn container_rows(&self) -> Vec<(Pid, Vec<String>)> {
+        // The largest process of each container, the process count, CPU usage and memory.
+        let mut groups = HashMap::<String, (Pid, usize, f64, u64)>::new();
+        // Threads are accounted for in their process.
+        for (pid, summary) in &self.process_summaries {
+            let Some(process) = self.system.process(*pid) else {
+                continue;
+            };
+            if !self.is_process_shown(*pid) {
+                continue;
+            }
+            let name = summary
+                .container
+                .as_ref()
+                .map_or_else(|| "host".to_string(), Container::to_string);
+            let group = groups.entry(name).or_insert((*pid, 0, 0.0, 0));
+            if process.memory() > self.system.process(group.0).map_or(0, |p| p.memory()) {
+                group.0 = *pid;
+            }
+            group.1 += 1;
+            group.2 += process.cpu_usage() as f64;
+            group.3 += process.memory();
+        }
+        groups
+            .into_iter()
+            .map(|(name, (pid, processes, cpu_usage, memory))| {
+                let row = vec![
+                    processes.to_string(),
+                    name,
+                    format!("{:.2}", self.cpu_percent(cpu_usage)),
+                    format!(
+                        "{:.2}",
+                        memory as f64 / self.system.total_memory() as f64 * 100.0
+                    ),
+                ];
+                (pid, row)
+            })
+            .collect()
+    }
+
+    /// Shows the processes of the selected container.
+    fn show_selected_container(&mut self) {
+        // Rows of containers are keyed by one of their processes.
+        let Some(pid) = self.selected_pid() else {
+            return;
+        };
+        self.container_filter = Some(self.process_container(pid).cloned());
+        self.group_by_container = false;
+        self.table_state.select(Some(0));
+    }
+
+    /// Whether the process is in the focused tree and the filtered cgroup and container, if
+    /// any.
+    fn is_process_shown(&self, pid: Pid) -> bool {
+        self.scope.is_none_or(|root| self.is_descendant(pid, root))
+            && (self.cgroup_filter.is_none() || self.cgroup_threads.contains(&pid))
+            && self
+                .container_filter
+                .as_ref()
+                .is_none_or(|container| self.process_container(pid) == container.as_ref())
+    }
+
+    /// Returns the container of a process, or of the process of a thread.
+    fn process_container(&self, pid: Pid) -> Option<&Container> {
+        let summary = self.process_summaries.get(&pid).or_else(|| {
+            let process = self.system.process(pid)?;
+            process.thread_kind()?;
+            self.process_summaries.get(&process.parent()?)
+        })?;
+        summary.container.as_ref()
+    }
+
+    /// Returns the events that are recent enough to be highlighted in the process table.
+    fn recent_process_events(&self) -> impl Iterator<Item = &ProcessEvent> {
+        self.process_events.iter().rev().take_while(|event| {
+            event
+                .time
+                .elapsed()
+                .is_ok_and(|elapsed| elapsed < EVENT_HIGHLIGHT_DURATION)
+        })
+    }
+
+    /// Renders a table of spawned and exited processes, the most recent first.
+    fn render_events(&mut self, frame: &mut Frame, area: Rect) {
+        let header = Row::new(vec![
+            "Time",
+            "Event",
+            "Pid",
+            "Parent",
+            "Name",
+            "Lifetime",
+            "Peak CPU%",
+            "Peak Mem",
+        ])
+        .style(tailwind::YELLOW.c200);
+
+        let widths = [
+            Length(8),
+            Length(7),
+            Length(10),
+            Length(10),
+            Fill(2),
+            Length(10),
+            Length(10),
+            Length(10),
+        ];
+
+        let rows = self
+            .process_events
+            .iter()
+            .rev()
+            .map(|event| {
+                let (kind, color, lifetime) = match event.kind {
+                    ProcessEventKind::Spawned => ("spawn", tailwind::GREEN.c400, String::new()),
+                    ProcessEventKind::Exited => (
+                        "exit",
+                        tailwind::RED.c400,
+                        format_duration(
+                            event
+                                .time
+                                .duration_since(event.summary.start_time)
+                                .unwrap_or_default(),
+                        ),
+                    ),
+                };
+                Row::new(vec![
+                    format_time(event.time),
+                    kind.to_string(),
+                    event.pid.to_string(),
+                    event
+                        .summary
+                        .parent
+                        .map(|pid| pid.to_string())
+                        .unwrap_or_default(),
+                    event.summary.name.clone(),
+                    lifetime,
+                    // Processes that exit before the next refresh are never measured.
+                    event
+                        .summary
+                        .peak_cpu_usage
+                        .map(|peak| format!("{:.2}", self.cpu_percent(peak)))
+                        .unwrap_or_else(|| "-".to_string()),
+                    format_bytes(event.summary.peak_memory),
+                ])
+                .fg(color)
+            })
+            .collect::<Vec<_>>();
+
+        let table = Table::new(rows, widths)
+            .header(header)
+            .style(tailwind::GRAY.c900)
+            .row_highlight_style(Style::new().bg(tailwind::GRAY.c800).fg(tailwind::BLUE.c200))
+            .highlight_symbol("> ")
+            .block(Self::create_pane("Events"));
+
+        frame.render_stateful_widget(table, area, &mut self.event_table_state);
+    }
+
+    /// Renders the processes suspected of leaking memory and the trend of the selected one.
+    ///
+    /// The resulting layout is as follows:
+    ///
+    /// ```
+    /// ┌Suspects──────────────────────────────────────────────────┐
+    /// │                                                          │
+    /// └──────────────────────────────────────────────────────────┘
+    /// ┌Trend─────────────────────────────────────────────────────┐
+    /// │                                                          │
+    /// └──────────────────────────────────────────────────────────┘
+    /// ```
+    fn render_suspects(&mut self, frame: &mut Frame, area: Rect) {
+        let [table_area, chart_area] = Layout::vertical([Fill(1); 2]).areas(area);
+
+        let header = Row::new(vec!["Pid", "Name", "Memory", "Growth", "Trend", "Full in"])
+            .style(tailwind::YELLOW.c200);
+        let widths = [
+            Length(10),
+            Fill(2),
+            Length(10),
+            Length(12),
+            Length(SPARKLINE_LEN as u16),
+            Length(10),
+        ];
+
+        let available_memory = self.system.available_memory() as f64;
+        let rows = self
+            .leak_suspects
+            .iter()
+            .map(|suspect| {
+                let samples = self
+                    .memory_trends
+                    .get(&suspect.pid)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                let trend = samples[samples.len().saturating_sub(SPARKLINE_LEN)..]
+                    .iter()
+                    .map(|v| v.memory as f64)
+                    .collect::<Vec<_>>();
+                let memory = samples.last().map(|v| v.memory).unwrap_or_default();
+                let hours_left = available_memory / suspect.growth_rate;
+                Row::new(vec![
+                    suspect.pid.to_string(),
+                    self.process_summaries
+                        .get(&suspect.pid)
+                        .map(|summary| summary.name.clone())
+                        .unwrap_or_default(),
+                    format_bytes(memory),
+                    format!("{}/h", format_bytes(suspect.growth_rate as u64)),
+                    Self::create_sparkline(&trend, 0.0),
+                    format_duration(Duration::from_secs_f64(hours_left * 3600.0)),
+                ])
+                .fg(tailwind::ORANGE.c400)
+            })
+            .collect::<Vec<_>>();
+
+        let table = Table::new(rows, widths)
+            .header(header)
+            .style(tailwind::GRAY.c900)
+            .row_highlight_style(Style::new().bg(tailwind::GRAY.c800).fg(tailwind::BLUE.c200))
+            .highlight_symbol("> ")
+            .block(Self::create_pane("Suspects"));
+
+        frame.render_stateful_widget(table, table_area, &mut self.suspect_table_state);
+
+        let suspect = self
+            .suspect_table_state
+            .selected()
+            .and_then(|index| self.leak_suspects.get(index));
+        let points = suspect
+            .and_then(|suspect| self.memory_trends.get(&suspect.pid))
+            .map(|samples| Self::memory_trend_points(samples))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(time, memory)| (time, memory / 1024.0 / 1024.0))
+            .collect::<Vec<_>>();
+        let trend = suspect
+            .zip(points.first().zip(points.last()))
+            .map(|(suspect, (first, last))| {
+                let slope = suspect.growth_rate / 3600.0 / 1024.0 / 1024.0;
+                let intercept = suspect.intercept / 1024.0 / 1024.0;
+                vec![
+                    (first.0, intercept + slope * first.0),
+                    (last.0, intercept + slope * last.0),
+                ]
+            })
+            .unwrap_or_default();
+
+        let datasets = vec![
+            Dataset::default()
+                .name("RSS (MiB)")
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(tailwind::BLUE.c400)
+                .data(&points),
+            Dataset::default()
+                .name("Trend")
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(tailwind::ORANGE.c400)
+                .data(&trend),
+        ];
+
+        let max_x = points.last().map(|v| v.0).unwrap_or_default();
+        let max_y = points.iter().chain(&trend).map(|v| v.1).fold(1.0, f64::max);
+        let x_axis = Axis::default()
+            .bounds([0.0, max_x])
+            .labels(vec!["0m".to_string(), format!("{:.0}m", max_x / 60.0)])
+            .style(tailwind::GRAY.c600);
+        let y_axis = Axis::default()
+            .bounds([0.0, max_y])
+            .labels(vec!["0".to_string(), format!("{max_y:.0}")])
+            .style(tailwind::GRAY.c600);
+
+        let chart = Chart::new(datasets)
+            .block(Self::create_pane("Trend").title_alignment(Alignment::Right))
+            .style(Style::new().bg(tailwind::GRAY.c900))
+            .x_axis(x_axis)
+            .y_axis(y_axis);
+
+        frame.render_widget(chart, chart_area);
+    }
+
+    /// Renders the processes that used the most resources during the selected time window,
+    /// including the ones that have exited.
+    fn render_ranking(&mut self, frame: &mut Frame, area: Rect) {
+        let window = self.ranking_window.minutes();
+        if self.ranking.is_none() {
+            self.ranking = Some(self.merge_ranking());
+        }
+        let observed = self
+            .usage_buckets
+            .get(self.usage_buckets.len().saturating_sub(window))
+            .map(|bucket| bucket.start.elapsed().as_secs_f64())
+            .unwrap_or_default();
+
+        let mut header = vec!["Pid", "Name", "CPU Time", "Avg CPU%", "Avg Mem", "Peak Mem"];
+        let sorted_column = match self.ranking_metric {
+            RankingMetric::CpuTime => 2,
+            RankingMetric::AverageMemory => 4,
+            RankingMetric::PeakMemory => 5,
+        };
+        let sorted_header = format!("{}▼", header[sorted_column]);
+        header[sorted_column] = &sorted_header;
+        header.push("State");
+        let header = Row::new(header).style(tailwind::YELLOW.c200);
+        let widths = [
+            Length(10),
+            Fill(2),
+            Length(10),
+            Length(10),
+            Length(10),
+            Length(10),
+            Length(8),
+        ];
+
+        let rows = self
+            .ranking
+            .iter()
+            .flatten()
+            .map(|(pid, usage)| {
+                let running = self.process_summaries.contains_key(pid);
+                let average_cpu = if observed > 0.0 {
+                    self.cpu_percent(usage.cpu_time / observed * 100.0)
+                } else {
+                    0.0
+                };
+                Row::new([
+                    Cell::from(pid.to_string()),
+                    Cell::from(usage.name.as_str()),
+                    Cell::from(format_duration(Duration::from_secs_f64(usage.cpu_time))),
+                    Cell::from(format!("{average_cpu:.2}")),
+                    Cell::from(format_bytes(usage.average_memory())),
+                    Cell::from(format_bytes(usage.peak_memory)),
+                    Cell::from(if running { "running" } else { "exited" }),
+                ])
+                .fg(if running {
+                    tailwind::GRAY.c400
+                } else {
+                    tailwind::RED.c400
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let title = format!("Top consumers (last {window}m)");
+        let table = Table::new(rows, widths)
+            .header(header)
+            .style(tailwind::GRAY.c900)
+            .row_highlight_style(Style::new().bg(tailwind::GRAY.c800).fg(tailwind::BLUE.c200))
+            .highlight_symbol("> ")
+            .block(Self::create_pane(&title));
+
+        frame.render_stateful_widget(table, area, &mut self.ranking_table_state);
+    }
+
+    /// Merges the usage buckets of the ranking window and sorts the processes by the ranking
+    /// metric.
+    fn merge_ranking(&self) -> Vec<(Pid, ProcessUsage)> {
+        let mut usage = HashMap::<Pid, ProcessUsage>::new();
+        for bucket in self
+            .usage_buckets
+            .iter()
+            .rev()
+            .take(self.ranking_window.minutes())
+        {
+            for (pid, bucket_usage) in &bucket.usage {
+                usage.entry(*pid).or_default().merge(bucket_usage);
+            }
+        }
+
+        let mut usage = usage.into_iter().collect::<Vec<_>>();
+        match self.ranking_metric {
+            RankingMetric::CpuTime => usage.sort_by(|a, b| b.1.cpu_time.total_cmp(&a.1.cpu_time)),
+            RankingMetric::AverageMemory => {
+                usage.sort_by_key(|(_, usage)| std::cmp::Reverse(usage.average_memory()))
+            }
+            RankingMetric::PeakMemory => {
+                usage.sort_by_key(|(_, usage)| std::cmp::Reverse(usage.peak_memory))
+            }
+        }
+        usage
+    }
+
+    /// Renders the listening sockets and their owning process.
+    fn render_sockets(&mut self, frame: &mut Frame, area: Rect) {
+        let header =
+            Row::new(vec!["Proto", "Local Address", "Pid", "Name"]).style(tailwind::YELLOW.c200);
+        let widths = [Length(6), Fill(2), Length(10), Fill(1)];
+
+        let rows = self
+            .sockets
+            .iter()
+            .map(|(socket, owners)| {
+                let name = owners
+                    .first()
+                    .and_then(|pid| self.process_summaries.get(pid))
+                    .map(|summary| summary.name.clone())
+                    .unwrap_or_default();
+                let pid = match owners.as_slice() {
+                    [] => String::new(),
+                    [pid] => pid.to_string(),
+                    [pid, others @ ..] => format!("{pid} +{}", others.len()),
+                };
+                Row::new(vec![
+                    socket.protocol.to_string(),
+                    socket.local_address.clone(),
+                    pid,
+                    name,
+                ])
+                .fg(tailwind::GRAY.c400)
+            })
+            .collect::<Vec<_>>();
+
+        let table = Table::new(rows, widths)
+            .header(header)
+            .style(tailwind::GRAY.c900)
+            .row_highlight_style(Style::new().bg(tailwind::GRAY.c800).fg(tailwind::BLUE.c200))
+            .highlight_symbol("> ")
+            .block(Self::create_pane("Sockets"));
+
+        frame.render_stateful_widget(table, area, &mut self.socket_table_state);
+    }
+
+    /// Flashes a banner over the header after an OOM kill.
+    fn render_oom_banner(&self, frame: &mut Frame, area: Rect) {
+        let Some(event) = self.oom_events.last() else {
+            return;
+        };
+        let Ok(elapsed) = event.time.elapsed() else {
+            return;
+        };
+        if elapsed >= OOM_BANNER_DURATION {
+            return;
+        }
+        let victim = match &event.victim {
+            Some(victim) => format!(
+                "{}{} ({}) using {}",
+                if victim.likely { "likely " } else { "" },
+                victim.name,
+                victim.pid,
+                format_bytes(victim.memory)
+            ),
+            None => "victim unknown".to_string(),
+        };
+        let cgroup = event
+            .cgroup
+            .as_ref()
+            .map(|path| format!(" │ cgroup {path}"))
+            .unwrap_or_default();
+        let text = format!(
+            "OOM KILL at {} │ {victim}{cgroup} │ O for details",
+            format_time(event.time)
+        );
+        let background = if elapsed.as_millis() / 500 % 2 == 0 {
+            tailwind::RED.c600
+        } else {
+            tailwind::RED.c900
+        };
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Line::from(text)
+                .alignment(Alignment::Center)
+                .fg(tailwind::WHITE)
+                .bg(background)
+                .bold(),
+            area,
+        );
+    }
+
+    /// Renders the processes killed by the OOM killer, the most recent first.
+    fn render_oom_events(&mut self, frame: &mut Frame, area: Rect) {
+        let header =
+            Row::new(vec!["Time", "Pid", "Name", "Memory", "Cgroup"]).style(tailwind::YELLOW.c200);
+        let widths = [Length(8), Length(10), Fill(1), Length(10), Fill(2)];
+
+        let rows = self
+            .oom_events
+            .iter()
+            .rev()
+            .map(|event| {
+                let (pid, name, memory) = match &event.victim {
+                    Some(victim) => {
+                        let mut name = victim.name.clone();
+                        if victim.likely {
+                            name.push_str(" (likely)");
+                        }
+                        (victim.pid.to_string(), name, format_bytes(victim.memory))
+                    }
+                    None => (String::new(), "unknown".to_string(), String::new()),
+                };
+                Row::new(vec![
+                    format_time(event.time),
+                    pid,
+                    name,
+                    memory,
+                    event.cgroup.clone().unwrap_or_default(),
+                ])
+                .fg(tailwind::RED.c400)
+            })
+            .collect::<Vec<_>>();
+
+        let title = format!("OOM kills ({})", self.oom_events.len());
+        let table = Table::new(rows, widths)
+            .header(header)
+            .style(tailwind::GRAY.c900)
+            .row_highlight_style(Style::new().bg(tailwind::GRAY.c800).fg(tailwind::BLUE.c200))
+            .highlight_symbol("> ")
+            .block(Self::create_pane(&title));
+
+        frame.render_stateful_widget(table, area, &mut self.oom_table_state);
+    }
+
+    /// Renders the hardware and system inventory, one section after the other.
+    fn render_inventory(&mut self, frame: &mut Frame, area: Rect) {
+        let sections = self
+            .inventory
+            .as_ref()
+            .map(Inventory::sections)
+            .unwrap_or_default();
+        let mut rows = Vec::new();
+        for (title, entries) in sections {
+            if !rows.is_empty() {
+                rows.push(Row::new(vec![String::new(); 2]));
+            }
+            rows.push(
+                Row::new(vec![title.to_string(), String::new()]).style(tailwind::YELLOW.c200),
+            );
+            rows.extend(
+                entries
+                    .into_iter()
+                    .map(|(label, value)| Row::new(vec![label, value]).fg(tailwind::GRAY.c400)),
+            );
+        }
+
+        let title = match &self.inventory_export {
+            Some(export) => format!("Inventory │ {export}"),
+            None => "Inventory │ x to export as text, X as JSON".to_string(),
+        };
+        let table = Table::new(rows, [Length(22), Fill(1)])
+            .style(tailwind::GRAY.c900)
+            .row_highlight_style(Style::new().bg(tailwind::GRAY.c800).fg(tailwind::BLUE.c200))
+            .highlight_symbol("> ")
+            .block(Self::create_pane(&title));
+
+        frame.render_stateful_widget(table, area, &mut self.inventory_table_state);
+    }
+
+    /// Writes the inventory to a new file of the current directory in the given format and
+    /// remembers where, to show it.
+    fn export_inventory(&mut self, format: InventoryFormat) {
+        let Some(inventory) = &self.inventory else {
+            return;
+        };
+        let (content, extension) = match format {
+            InventoryFormat::Text => (inventory.to_text(), "txt"),
+            InventoryFormat::Json => (inventory.to_json() + "\n", "json"),
+        };
+        use std::io::Write;
+
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let path = std::env::current_dir()
+            .unwrap_or_default()
+            .join(format!("ratatop-inventory-{time}.{extension}"));
+        // Existing files, or links planted in their place, are never overwritten.
+        let written = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(content.as_bytes()));
+        self.inventory_export = Some(match written {
+            Ok(()) => format!("exported to {}", path.display()),
+            Err(error) => format!("cannot export to {}: {error}", path.display()),
+        });
+    }
+
+    /// Renders the cgroup tree with the usage and limits of each cgroup.
+    ///
+    /// Cgroups that were throttled or had processes killed by the OOM killer are highlighted.
+    fn render_cgroups(&mut self, frame: &mut Frame, area: Rect) {
+        if self.cgroup_root.is_none() {
+            let block = Self::create_pane("Cgroups");
+            let inner_area = block.inner(area);
+            frame.render_widget(block, area);
+            let line = "The cgroup v2 hierarchy is not mounted.";
+            frame.render_widget(Line::from(line).fg(tailwind::GRAY.c400), inner_area);
+            return;
+        }
+
+        let header = Row::new(vec![
+            "Cgroup",
+            "CPU%",
+            "CPU Max",
+            "Throttled",
+            "Thr. Time",
+            "Memory",
+            "Mem Max",
+            "Mem High",
+            "OOM",
+            "OOM Kill",
+        ])
+        .style(tailwind::YELLOW.c200);
+        let widths = [
+            Fill(1),
+            Length(8),
+            Length(8),
+            Length(10),
+            Length(10),
+            Length(10),
+            Length(10),
+            Length(10),
+            Length(6),
+            Length(8),
+        ];
+
+        let limit = |limit: Option<u64>| limit.map(format_bytes).unwrap_or_else(|| "-".to_string());
+        let rows = self
+            .cgroups
+            .iter()
+            .map(|details| {
+                let cgroup = &details.cgroup;
+                let mut name = format!("{}{}", "  ".repeat(cgroup.depth), cgroup.name());
+                if self.cgroup_filter.as_ref() == Some(&cgroup.path) {
+                    name.push_str(" *");
+                }
+                let color = if cgroup.oom_kill > 0 {
+                    tailwind::RED.c400
+                } else if details.throttled > 0.0 {
+                    tailwind::ORANGE.c400
+                } else {
+                    tailwind::GRAY.c400
+                };
+                Row::new(vec![
+                    name,
+                    format!("{:.1}", details.cpu_usage),
+                    details
+                        .cpu_limit()
+                        .map(|limit| format!("{limit:.0}%"))
+                        .unwrap_or_else(|| "-".to_string()),
+                    format!("{:.1}%", details.throttled),
+                    format_duration(Duration::from_micros(cgroup.throttled_time)),
+                    limit(cgroup.memory),
+                    limit(cgroup.memory_max),
+                    limit(cgroup.memory_high),
+                    cgroup.oom.to_string(),
+                    cgroup.oom_kill.to_string(),
+                ])
+                .fg(color)
+            })
+            .collect::<Vec<_>>();
+
+        let title = match &self.cgroup_filter {
+            Some(path) => format!("Cgroups │ filtering processes to {path} (Esc to clear)"),
+            None => "Cgroups │ Enter to filter processes".to_string(),
+        };
+        let table = Table::new(rows, widths)
+            .header(header)
+            .style(tailwind::GRAY.c900)
+            .row_highlight_style(Style::new().bg(tailwind::GRAY.c800).fg(tailwind::BLUE.c200))
+            .highlight_symbol("> ")
+            .block(Self::create_pane(&title));
+        frame.render_stateful_widget(table, area, &mut self.cgroup_table_state);
+    }
+
+    /// Filters the process table to the selected cgroup.
+    fn filter_selected_cgroup(&mut self) {
+        let Some(path) = self
+            .cgroup_table_state
+            .selected()
+            .and_then(|index| self.cgroups.get(index))
+            .map(|details| details.cgroup.path.clone())
+        else {
+            return;
+        };
+        self.cgroup_filter = Some(path);
+        self.refresh_cgroup_threads();
+        self.screen = Screen::Dashboard;
+        self.focus = Focus::Processes;
+    }
+
+    /// Renders a chart of the pressure on each resource.
+    fn render_pressure(&self, frame: &mut Frame, area: Rect) {
+        if self.pressure_data.is_empty() {
+            let block = Self::create_pane("Pressure");
+            let inner_area = block.inner(area);
+            frame.render_widget(block, area);
+            let line = "Pressure Stall Information is not available on this kernel.";
+            frame.render_widget(Line::from(line).fg(tailwind::GRAY.c400), inner_area);
+            return;
+        }
+
+        let areas = Layout::vertical([Fill(1); 3]).split(area);
+        for (resource, area) in Resource::ALL.into_iter().zip(areas.iter()) {
+            let data = self
+                .pressure_data
+                .get(&resource)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            self.render_pressure_chart(frame, *area, resource, data);
+        }
+    }
+
+    /// Renders the `some` and `full` averages of the pressure on a resource over time.
+    fn render_pressure_chart(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        resource: Resource,
+        data: &[PressureData],
+    ) {
+        let points = |value: fn(&Pressure) -> f64| {
+            data.iter()
+                .map(|v| (v.point, value(&v.pressure)))
+                .collect::<Vec<_>>()
+        };
+        let series = [
+            ("some 10s", tailwind::YELLOW.c300, points(|v| v.some.avg10)),
+            ("some 60s", tailwind::YELLOW.c500, points(|v| v.some.avg60)),
+            (
+                "some 300s",
+                tailwind::YELLOW.c700,
+                points(|v| v.some.avg300),
+            ),
+            ("full 10s", tailwind::RED.c400, points(|v| v.full.avg10)),
+            ("full 60s", tailwind::RED.c600, points(|v| v.full.avg60)),
+            ("full 300s", tailwind::RED.c800, points(|v| v.full.avg300)),
+        ];
+        let datasets = series
+            .iter()
+            .map(|(name, color, points)| {
+                Dataset::default()
+                    .name(*name)
+                    .marker(Marker::Braille)
+                    .graph_type(GraphType::Line)
+                    .style(*color)
+                    .data(points)
+            })
+            .collect::<Vec<_>>();
+
+        // Stalls are usually low, so the chart is scaled to the highest value in steps of 10%.
+        let max = series
+            .iter()
+            .flat_map(|(_, _, points)| points)
+            .map(|(_, value)| *value)
+            .fold(0.0, f64::max);
+        let max = ((max / 10.0).ceil() * 10.0).clamp(10.0, 100.0);
+
+        let title = match data.last() {
+            Some(v) => format!(
+                "{resource} │ some {:.2} {:.2} {:.2} │ full {:.2} {:.2} {:.2}",
+                v.pressure.some.avg10,
+                v.pressure.some.avg60,
+                v.pressure.some.avg300,
+                v.pressure.full.avg10,
+                v.pressure.full.avg60,
+                v.pressure.full.avg300,
+            ),
+            None => format!("{resource} │ not available"),
+        };
+        let bounds = match (data.first(), data.last()) {
+            (Some(first), Some(last)) => [first.point, last.point.max(first.point + 1.0)],
+            _ => [0.0, 1.0],
+        };
+        let x_axis = Axis::default().bounds(bounds);
+        let y_axis = Axis::default()
+            .bounds([0.0, max])
+            .labels(vec!["0%".to_string(), format!("{max}%")])
+            .style(tailwind::GRAY.c600);
+
+        let chart = Chart::new(datasets)
+            .block(Self::create_pane(&title))
+            .style(Style::new().bg(tailwind::GRAY.c900))
+            .x_axis(x_axis)
+            .y_axis(y_axis)
+            // The legend has a line per series, keep it unless the chart is tiny.
+            .hidden_legend_constraints((Ratio(1, 4), Ratio(3, 4)));
+        frame.render_widget(chart, area);
+    }
+
+    /// Renders the network interfaces and the details of the selected one.
+    ///
+    /// The resulting layout is as follows:
+    ///
+    /// ```
+    /// ┌Interfaces────────────────────────────────────────────────┐
+    /// │                                                          │
+    /// └──────────────────────────────────────────────────────────┘
+    /// ┌Details───────────────────────────────────────────────────┐
+    /// │                                                          │
+    /// └──────────────────────────────────────────────────────────┘
+    /// ```
+    fn render_interfaces(&mut self, frame: &mut Frame, area: Rect) {
+        let [table_area, details_area] = Layout::vertical([Fill(1); 2]).areas(area);
+
+        let mut interfaces = self.interfaces.iter().collect::<Vec<_>>();
+        interfaces.sort_by_key(|(name, _)| *name);
+
+        let header = Row::new(vec![
+            "Interface",
+            "State",
+            "Speed",
+            "Received",
+            "Transmitted",
+            "Rx/s",
+            "Tx/s",
+            "Util%",
+        ])
+        .style(tailwind::YELLOW.c200);
+        let widths = [
+            Fill(1),
+            Length(10),
+            Length(12),
+            Length(12),
+            Length(12),
+            Length(12),
+            Length(12),
+            Length(8),
+        ];
+        let rows = interfaces
+            .iter()
+            .map(|(name, details)| {
+                let color = match details.link.operstate.as_str() {
+                    "down" => tailwind::RED.c400,
+                    _ if details.error_rate > 0.0 || details.drop_rate > 0.0 => {
+                        tailwind::ORANGE.c400
+                    }
+                    _ => tailwind::GRAY.c400,
+                };
+                Row::new(vec![
+                    name.to_string(),
+                    details.link.operstate.clone(),
+                    details
+                        .link
+                        .speed
+                        .map(|speed| format!("{speed} Mbit/s"))
+                        .unwrap_or_default(),
+                    format_bytes(details.link.received_bytes),
+                    format_bytes(details.link.transmitted_bytes),
+                    format_bytes(details.receive_rate as u64),
+                    format_bytes(details.transmit_rate as u64),
+                    details
+                        .utilization()
+                        .map(|utilization| format!("{utilization:.1}"))
+                        .unwrap_or_default(),
+                ])
+                .fg(color)
+            })
+            .collect::<Vec<_>>();
+
+        let table = Table::new(rows, widths)
+            .header(header)
+            .style(tailwind::GRAY.c900)
+            .row_highlight_style(Style::new().bg(tailwind::GRAY.c800).fg(tailwind::BLUE.c200))
+            .highlight_symbol("> ")
+            .block(Self::create_pane("Interfaces"));
+        frame.render_stateful_widget(table, table_area, &mut self.interface_table_state);
+
+        let Some((name, details)) = self
+            .interface_table_state
+            .selected()
+            .and_then(|index| interfaces.get(index))
+        else {
+            frame.render_widget(Self::create_pane("Details"), details_area);
+            return;
+        };
+
+        let addresses = self
+            .networks
+            .get(*name)
+            .map(|network| {
+                network
+                    .ip_networks()
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let link = &details.link;
+        let mut rows = vec![
+            ("Addresses", addresses.join(", ")),
+            ("MAC", link.mac_address.clone()),
+            ("MTU", link.mtu.to_string()),
+            ("State", link.operstate.clone()),
+            (
+                "Speed",
+                link.speed
+                    .map(|speed| format!("{speed} Mbit/s"))
+                    .unwrap_or_else(|| "unknown".to_string()),
+            ),
+            ("Received", format_bytes(link.received_bytes)),
+            ("Transmitted", format_bytes(link.transmitted_bytes)),
+            (
+                "Errors",
+                format!("{} ({:.1}/s)", link.errors, details.error_rate),
+            ),
+            (
+                "Drops",
+                format!("{} ({:.1}/s)", link.dropped, details.drop_rate),
+            ),
+            (
+                "Collisions",
+                format!("{} ({:.1}/s)", link.collisions, details.collision_rate),
+            ),
+        ];
+        if let Some(utilization) = details.utilization() {
+            rows.push(("Utilization", format!("{utilization:.1}%")));
+        }
+        let rows = rows
+            .into_iter()
+            .map(|(key, value)| Row::new(vec![key.to_string(), value]).fg(tailwind::GRAY.c400))
+            .collect::<Vec<_>>();
+        let title = format!("Details │ {name}");
+        let table = Table::new(rows, [Length(12), Fill(1)])
+            .style(tailwind::GRAY.c900)
+            .block(Self::create_pane(&title));
+        frame.render_widget(table, details_area);
+    }
+
+    /// Shows the disk usage of the selected disk, scanning it unless already done.
+    fn explore_selected_disk(&mut self) {
+        let Some(disk) = self.disk_data.get(self.selected_disk) else {
+            return;
+        };
+        if self
+            .scan
+            .as_ref()
+            .is_none_or(|scan| scan.root != disk.mount_point)
+        {
+            self.start_scan(disk.mount_point.clone());
+        }
+        self.screen = Screen::Explorer;
+    }
+
+    /// Scans the given directory from scratch and browses its root.
+    fn start_scan(&mut self, root: PathBuf) {
+        self.scan = Some(Scan::start(root));
+        self.explorer_path.clear();
+        self.explorer_table_state.select(Some(0));
+    }
+
+    /// Browses into the selected directory of the explorer.
+    ///
+    /// The entries of the root are still added while it is scanned, so the directories are
+    /// found by name.
+    fn explore_into_selected(&mut self) {
+        let Some(index) = self.explorer_table_state.selected() else {
+            return;
+        };
+        let name = self
+            .explored_directory()
+            .and_then(|directory| directory.children.get(index))
+            .filter(|child| !child.children.is_empty() || child.other_entries > 0)
+            .map(|child| child.name.clone());
+        if let Some(name) = name {
+            self.explorer_path.push(name);
+            self.explorer_table_state.select(Some(0));
         }
+    }
 
-        let text = self.textarea.lines().first().unwrap();
-        rows.retain(|row| {
-            row.iter()
-                .any(|cell| cell.to_lowercase().contains(&text.to_lowercase()))
+    /// Browses back to the parent directory in the explorer, selecting the one left.
+    fn explore_parent(&mut self) {
+        let Some(name) = self.explorer_path.pop() else {
+            return;
+        };
+        let index = self.explored_directory().and_then(|directory| {
+            directory
+                .children
+                .iter()
+                .position(|child| child.name == name)
         });
+        self.explorer_table_state
+            .select(Some(index.unwrap_or_default()));
+    }
 
-        rows.sort_by(|a, b| {
-            a[2].parse::<f64>()
-                .unwrap_or_default()
-                .partial_cmp(&b[2].parse::<f64>().unwrap_or_default())
-                .unwrap()
-                .reverse()
-        });
+    /// Returns the directory browsed into in the explorer, once the scan has started.
+    fn explored_directory(&self) -> Option<&Node> {
+        let mut directory = self.scan.as_ref()?.tree.as_ref()?;
+        for name in &self.explorer_path {
+            directory = directory
+                .children
+                .iter()
+                .find(|child| child.name == *name)?;
+        }
+        Some(directory)
+    }
 
-        let table = Table::new(
-            rows.into_iter()
-                .map(|v| Row::new(v).fg(tailwind::GRAY.c400))
-                .collect::<Vec<_>>(),
-            widths,
-        )
-        .header(header)
-        .style(tailwind::GRAY.c900)
-        .row_highlight_style(Style::new().bg(tailwind::GRAY.c800).fg(tailwind::BLUE.c200))
-        .highlight_symbol("> ")
-        .block(Self::create_pane("Processes"));
+    /// Renders the disk usage of the browsed directory, the largest entries first.
+    fn render_explorer(&mut self, frame: &mut Frame, area: Rect) {
+        let Some(scan) = &self.scan else {
+            return;
+        };
+        let Some(directory) = self.explored_directory() else {
+            let block = Self::create_pane("Disk usage");
+            let inner_area = block.inner(area);
+            frame.render_widget(block, area);
+            let line = format!(
+                "Scanning {}… {} files, {}",
+                scan.root.display(),
+                scan.files,
+                format_bytes(scan.size)
+            );
+            frame.render_widget(Line::from(line).fg(tailwind::GRAY.c400), inner_area);
+            return;
+        };
 
-        frame.render_stateful_widget(table, area, &mut self.table_state);
+        let mut path = scan.root.clone();
+        path.extend(&self.explorer_path);
+
+        let header = Row::new(vec!["Size", "%", "", "Name"]).style(tailwind::YELLOW.c200);
+        let widths = [
+            Length(10),
+            Length(6),
+            Length(SPARKLINE_LEN as u16 * 2),
+            Fill(1),
+        ];
+        let row = |size: u64, name: String| {
+            let percent = directory.share(size);
+            let bar_len = (percent / 100.0 * SPARKLINE_LEN as f64 * 2.0).round() as usize;
+            Row::new(vec![
+                format_bytes(size),
+                format!("{percent:.1}"),
+                "█".repeat(bar_len),
+                name,
+            ])
+        };
+        let mut rows = directory
+            .children
+            .iter()
+            .map(|child| row(child.size, format!("{}/", child.name)).fg(tailwind::BLUE.c200))
+            .collect::<Vec<_>>();
+        // Files are not kept, only their total.
+        if directory.other_entries > 0 {
+            let name = format!(
+                "(files and smaller directories: {})",
+                directory.other_entries
+            );
+            rows.push(row(directory.other_size, name).fg(tailwind::GRAY.c400));
+        }
+
+        let mut title = format!(
+            "Disk usage │ {} │ {}",
+            path.display(),
+            format_bytes(directory.size)
+        );
+        if !scan.done {
+            title.push_str(&format!(
+                " │ scanning… {} files, {}",
+                scan.files,
+                format_bytes(scan.size)
+            ));
+        }
+        // The selection is cleared while the root has no entries yet.
+        if !rows.is_empty() && self.explorer_table_state.selected().is_none() {
+            self.explorer_table_state.select(Some(0));
+        }
+        let table = Table::new(rows, widths)
+            .header(header)
+            .style(tailwind::GRAY.c900)
+            .row_highlight_style(Style::new().bg(tailwind::GRAY.c800).fg(tailwind::BLUE.c200))
+            .highlight_symbol("> ")
+            .block(Self::create_pane(&title));
+        frame.render_stateful_widget(table, area, &mut self.explorer_table_state);
+    }
+
+    /// Shows the dashboard with the given process selected in the process table.
+    fn select_process(&mut self, pid: Pid) {
+        self.screen = Screen::Dashboard;
+        self.focus = Focus::Processes;
+        if !self
+            .process_rows()
+            .iter()
+            .any(|(row_pid, _)| *row_pid == pid)
+        {
+            self.textarea.select_all();
+            self.textarea.delete_line_by_end();
+        }
+        self.pending_selection = Some(pid);
+    }
+
+    /// Returns the PID of the selected process.
+    fn selected_pid(&self) -> Option<Pid> {
+        let index = self.table_state.selected()?;
+        self.process_rows().get(index).map(|(pid, _)| *pid)
+    }
+
+    /// Renders a popup with the history of the inspected process.
+    ///
+    /// The resulting layout is as follows:
+    ///
+    /// ```
+    /// ┌Process─────────────────────────────────────────┐
+    /// │┌CPU%──────────────────┐┌Memory────────────────┐│
+    /// ││                      ││                      ││
+    /// │└──────────────────────┘└──────────────────────┘│
+    /// │┌Threads───────────────┐┌I/O───────────────────┐│
+    /// ││                      ││                      ││
+    /// │└──────────────────────┘└──────────────────────┘│
+    /// └────────────────────────────────────────────────┘
+    /// ```
+    fn render_process_detail(&self, frame: &mut Frame, area: Rect, pid: Pid) {
+        let [area] = Layout::horizontal([Percentage(90)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::vertical([Percentage(90)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        let title = match self.system.process(pid) {
+            Some(process) => format!("Process {pid} ({})", process.name().to_string_lossy()),
+            None => format!("Process {pid} (exited)"),
+        };
+        let block = Self::create_pane(&title);
+        let inner_area = block.inner(area);
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let [top, bottom, connections_area] =
+            Layout::vertical([Fill(2), Fill(2), Fill(1)]).areas(inner_area);
+        let [cpu_area, memory_area] = Layout::horizontal([Fill(1); 2]).areas(top);
+        let [threads_area, io_area] = Layout::horizontal([Fill(1); 2]).areas(bottom);
+
+        let history = self
+            .process_data
+            .get(&pid)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let points = |value: fn(&ProcessData) -> f64| {
+            history
+                .iter()
+                .map(|v| (v.point, value(v)))
+                .collect::<Vec<_>>()
+        };
+
+        let cpu = points(|v| v.cpu_usage)
+            .into_iter()
+            .map(|(point, usage)| (point, self.cpu_percent(usage)))
+            .collect::<Vec<_>>();
+        let current_cpu = cpu.last().map(|v| v.1).unwrap_or_default();
+        Self::render_history_chart(
+            frame,
+            cpu_area,
+            "CPU%",
+            &cpu,
+            format!("{current_cpu:.2}%"),
+            tailwind::GREEN.c400,
+        );
+
+        let memory = points(|v| v.memory as f64 / 1024.0 / 1024.0);
+        let current_memory = memory.last().map(|v| v.1).unwrap_or_default();
+        Self::render_history_chart(
+            frame,
+            memory_area,
+            "Memory",
+            &memory,
+            format!("{current_memory:.1} MiB"),
+            tailwind::BLUE.c400,
+        );
+
+        let threads = points(|v| v.threads as f64);
+        let current_threads = threads.last().map(|v| v.1).unwrap_or_default();
+        Self::render_history_chart(
+            frame,
+            threads_area,
+            "Threads",
+            &threads,
+            format!("{current_threads}"),
+            tailwind::YELLOW.c300,
+        );
+
+        let io = points(|v| v.io_rate / 1024.0);
+        let current_io = io.last().map(|v| v.1).unwrap_or_default();
+        Self::render_history_chart(
+            frame,
+            io_area,
+            "I/O",
+            &io,
+            format!("{current_io:.1} KiB/s"),
+            tailwind::PURPLE.c400,
+        );
+
+        self.render_connections(frame, connections_area);
+    }
+
+    /// Renders the sockets of the inspected process along with the number of sockets in
+    /// each state.
+    fn render_connections(&self, frame: &mut Frame, area: Rect) {
+        let [table_area, states_area] = Layout::horizontal([Fill(3), Fill(1)]).areas(area);
+
+        let header = Row::new(vec![
+            "Proto",
+            "Local Address",
+            "Remote Address",
+            "State",
+            "Send-Q",
+            "Recv-Q",
+        ])
+        .style(tailwind::YELLOW.c200);
+        let widths = [
+            Length(6),
+            Fill(1),
+            Fill(1),
+            Length(12),
+            Length(8),
+            Length(8),
+        ];
+        let rows = self
+            .connections
+            .iter()
+            .map(|socket| {
+                Row::new(vec![
+                    socket.protocol.to_string(),
+                    socket.local_address.clone(),
+                    socket.remote_address.clone(),
+                    socket.state_name().to_string(),
+                    socket.send_queue.to_string(),
+                    socket.receive_queue.to_string(),
+                ])
+                .fg(tailwind::GRAY.c400)
+            })
+            .collect::<Vec<_>>();
+        let table = Table::new(rows, widths)
+            .header(header)
+            .style(tailwind::GRAY.c900)
+            .block(Self::create_pane("Connections"));
+        frame.render_widget(table, table_area);
+
+        let mut states = HashMap::<&str, usize>::new();
+        for socket in &self.connections {
+            *states.entry(socket.state_name()).or_default() += 1;
+        }
+        let mut states = states.into_iter().collect::<Vec<_>>();
+        states.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        let rows = states
+            .into_iter()
+            .map(|(state, count)| {
+                Row::new(vec![state.to_string(), count.to_string()]).fg(tailwind::GRAY.c400)
+            })
+            .collect::<Vec<_>>();
+        let table = Table::new(rows, [Fill(1), Length(6)])
+            .header(Row::new(vec!["State", "Count"]).style(tailwind::YELLOW.c200))
+            .style(tailwind::GRAY.c900)
+            .block(Self::create_pane("States"));
+        frame.render_widget(table, states_area);
+    }
+
+    /// Renders a line chart of the given points, scaled to the largest value.
+    fn render_history_chart(
+        frame: &mut Frame,
+        area: Rect,
+        title: &str,
+        data: &[(f64, f64)],
+        current: String,
+        color: Color,
+    ) {
+        let datasets = vec![Dataset::default()
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(color)
+            .data(data)];
+
+        let min_x = data.first().map(|v| v.0).unwrap_or_default();
+        let max_x = data.last().map(|v| v.0).unwrap_or_default();
+        let max_y = data.iter().map(|v| v.1).fold(1.0, f64::max);
+
+        let x_axis = Axis::default()
+            .title(current.fg(color))
+            .bounds([min_x, max_x]);
+
+        let y_axis = Axis::default()
+            .bounds([0.0, max_y])
+            .labels(vec!["0".to_string(), format!("{max_y:.0}")])
+            .style(tailwind::GRAY.c600);
+
+        let chart = Chart::new(datasets)
+            .block(Self::create_pane(title).title_alignment(Alignment::Right))
+            .style(Style::new().bg(tailwind::GRAY.c900))
+            .x_axis(x_axis)
+            .y_axis(y_axis);
+
+        frame.render_widget(chart, area);
     }
 
     /// Renders a popup for search input.
-    fn render_search(&mut self, frame: &mut Frame<'_>, area: Rect) {
+    fn render_search(frame: &mut Frame<'_>, area: Rect, textarea: &TextArea<'_>) {
         let [search_area] = Layout::horizontal([Percentage(90)])
             .flex(Flex::Center)
             .areas(area);
@@ -423,11 +4368,28 @@ impl App {
             .areas(search_area);
 
         frame.render_widget(Clear, search_area);
-        frame.render_widget(&self.textarea, search_area);
+        frame.render_widget(textarea, search_area);
+    }
+
+    /// Creates an inline sparkline out of the given values.
+    ///
+    /// The values are scaled to the maximum of `max` and the largest value.
+    fn create_sparkline(data: &[f64], max: f64) -> String {
+        let max = data.iter().copied().fold(max, f64::max);
+        let levels = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+        data.iter()
+            .map(|value| {
+                if max <= 0.0 {
+                    return levels[0];
+                }
+                let level = (value / max * (levels.len() - 1) as f64).round() as usize;
+                levels[level.min(levels.len() - 1)]
+            })
+            .collect()
     }
 
     /// Creates a bordered block with a title.
-    fn create_pane(title: &str) -> Block {
+    fn create_pane(title: &str) -> Block<'_> {
         let title = Line::from_iter([
             "┤ ".fg(tailwind::GRAY.c700),
             title.fg(tailwind::BLUE.c200),
@@ -460,33 +4422,838 @@ impl App {
     /// Handles the key events and updates the state of [`App`].
     fn on_key_event(&mut self, key: KeyEvent) {
         if self.searching {
-            if key.code == KeyCode::Enter {
+            if matches!(key.code, KeyCode::Enter | KeyCode::Esc) {
                 self.searching = false;
                 return;
             } else {
-                self.textarea.input(key);
+                // Do not let the typed characters trigger the key bindings below.
+                self.search_textarea().input(key);
+                return;
             }
         }
+        if self.inspected_pid.is_some() && matches!(key.code, KeyCode::Enter | KeyCode::Esc) {
+            self.inspected_pid = None;
+            return;
+        }
+        // Esc clears the cgroup filter before leaving the screens that show it.
+        if self.cgroup_filter.is_some()
+            && key.code == KeyCode::Esc
+            && matches!(self.screen, Screen::Dashboard | Screen::Cgroups)
+        {
+            self.cgroup_filter = None;
+            self.cgroup_threads.clear();
+            return;
+        }
+        if self.container_filter.is_some()
+            && key.code == KeyCode::Esc
+            && self.screen == Screen::Dashboard
+        {
+            self.container_filter = None;
+            return;
+        }
+        if self.screen != Screen::Dashboard && key.code == KeyCode::Esc {
+            self.screen = Screen::Dashboard;
+            return;
+        }
         match (key.modifiers, key.code) {
             (_, KeyCode::Esc | KeyCode::Char('q'))
             | (KeyModifiers::CONTROL, KeyCode::Char('c') | KeyCode::Char('C')) => self.quit(),
+            (_, KeyCode::Tab) if self.screen == Screen::Dashboard => {
+                self.focus = self.focus.next();
+            }
+            (_, KeyCode::Char('g')) if self.screen == Screen::Dashboard => {
+                self.top_metric = self.top_metric.next();
+            }
+            (_, KeyCode::Char('m')) if self.screen == Screen::Dashboard => {
+                self.memory_view = self.memory_view.next();
+                if self.memory_view == MemoryView::Users {
+                    self.users.refresh();
+                }
+            }
+            (_, KeyCode::Char('j') | KeyCode::Down) if self.is_memory_focused() => {
+                let len = self
+                    .memory_groups()
+                    .iter()
+                    .map(|group| group.processes.len())
+                    .sum::<usize>();
+                self.selected_treemap_cell =
+                    (self.selected_treemap_cell + 1).min(len.saturating_sub(1));
+            }
+            (_, KeyCode::Char('k') | KeyCode::Up) if self.is_memory_focused() => {
+                self.selected_treemap_cell = self.selected_treemap_cell.saturating_sub(1);
+            }
+            (_, KeyCode::Enter) if self.is_memory_focused() => {
+                if let Some(pid) = self.selected_treemap_pid() {
+                    self.select_process(pid);
+                }
+            }
+            (_, KeyCode::Char('j') | KeyCode::Down) if self.is_disk_focused() => {
+                self.selected_disk =
+                    (self.selected_disk + 1).min(self.disk_data.len().saturating_sub(1));
+            }
+            (_, KeyCode::Char('k') | KeyCode::Up) if self.is_disk_focused() => {
+                self.selected_disk = self.selected_disk.saturating_sub(1);
+            }
+            (_, KeyCode::Enter) if self.is_disk_focused() => self.explore_selected_disk(),
+            (_, KeyCode::Enter | KeyCode::Char('l') | KeyCode::Right)
+                if self.screen == Screen::Explorer =>
+            {
+                self.explore_into_selected();
+            }
+            (_, KeyCode::Backspace | KeyCode::Char('h') | KeyCode::Left)
+                if self.screen == Screen::Explorer =>
+            {
+                self.explore_parent();
+            }
+            (_, KeyCode::Char('r')) if self.screen == Screen::Explorer => {
+                if let Some(root) = self.scan.as_ref().map(|scan| scan.root.clone()) {
+                    self.start_scan(root);
+                }
+            }
+            (_, KeyCode::Char('j') | KeyCode::Down) if self.is_network_focused() => {
+                self.network_scroll += 1;
+            }
+            (_, KeyCode::Char('k') | KeyCode::Up) if self.is_network_focused() => {
+                self.network_scroll = self.network_scroll.saturating_sub(1);
+            }
+            (_, KeyCode::Char('o')) if self.is_network_focused() => {
+                self.network_sort = self.network_sort.toggle();
+            }
+            (_, KeyCode::Char('b')) if self.is_network_focused() => {
+                self.hide_loopback = !self.hide_loopback;
+                self.refresh_interfaces();
+            }
+            (_, KeyCode::Char('d')) if self.is_network_focused() => {
+                self.hide_down = !self.hide_down;
+                self.refresh_interfaces();
+            }
+            (_, KeyCode::Char('v')) if self.is_network_focused() => {
+                self.hide_virtual = !self.hide_virtual;
+                self.refresh_interfaces();
+            }
             (_, KeyCode::Char('j') | KeyCode::Down) => {
-                self.table_state.select_next();
+                if let Some(table_state) = self.selected_table_state() {
+                    table_state.select_next();
+                }
             }
             (_, KeyCode::Char('k') | KeyCode::Up) => {
-                self.table_state.select_previous();
+                if let Some(table_state) = self.selected_table_state() {
+                    table_state.select_previous();
+                }
+            }
+            (_, KeyCode::Char('e')) => {
+                self.toggle_screen(Screen::Events);
+            }
+            (_, KeyCode::Char('L')) => {
+                self.toggle_screen(Screen::Suspects);
+            }
+            (_, KeyCode::Char('t')) => {
+                self.toggle_screen(Screen::Ranking);
+            }
+            (_, KeyCode::Char('w')) if self.screen == Screen::Ranking => {
+                self.ranking_window = self.ranking_window.next();
+                self.ranking = None;
+            }
+            (_, KeyCode::Char('o')) if self.screen == Screen::Ranking => {
+                self.ranking_metric = self.ranking_metric.next();
+                self.ranking = None;
+            }
+            (_, KeyCode::Char('h') | KeyCode::Left) => {
+                self.horizontal_scroll = self.horizontal_scroll.saturating_sub(4);
+            }
+            (_, KeyCode::Char('l') | KeyCode::Right) => {
+                self.horizontal_scroll += 4;
+            }
+            (_, KeyCode::Char('c')) => {
+                self.show_command = !self.show_command;
+                self.horizontal_scroll = 0;
+            }
+            (_, KeyCode::Char('G')) if self.screen == Screen::Dashboard => {
+                self.group_by_container = !self.group_by_container;
+                self.table_state.select(Some(0));
+            }
+            (_, KeyCode::Enter)
+                if self.screen == Screen::Dashboard
+                    && self.focus == Focus::Processes
+                    && self.group_by_container =>
+            {
+                self.show_selected_container();
+            }
+            (_, KeyCode::Enter)
+                if self.screen == Screen::Dashboard && self.focus == Focus::Processes =>
+            {
+                self.inspected_pid = self.selected_pid();
+                if let Some(pid) = self.inspected_pid {
+                    self.connections = sockets::process_connections(pid);
+                }
+            }
+            (_, KeyCode::Enter) if self.screen == Screen::Events => {
+                // The events are listed from the most recent one.
+                self.inspected_pid = self
+                    .event_table_state
+                    .selected()
+                    .and_then(|index| self.process_events.iter().rev().nth(index))
+                    .map(|event| event.pid);
+                if let Some(pid) = self.inspected_pid {
+                    self.connections = sockets::process_connections(pid);
+                }
+            }
+            (_, KeyCode::Enter) if self.screen == Screen::Sockets => {
+                let owner = self
+                    .socket_table_state
+                    .selected()
+                    .and_then(|index| self.sockets.get(index))
+                    .and_then(|(_, owners)| owners.first().copied());
+                if let Some(pid) = owner {
+                    self.select_process(pid);
+                }
+            }
+            (_, KeyCode::Char('n')) => {
+                self.toggle_screen(Screen::Interfaces);
+                if self.screen == Screen::Interfaces {
+                    self.refresh_interfaces();
+                }
+            }
+            (_, KeyCode::Char('P')) => self.toggle_screen(Screen::Pressure),
+            (_, KeyCode::Char('C')) => {
+                self.toggle_screen(Screen::Cgroups);
+                if self.screen == Screen::Cgroups {
+                    self.refresh_cgroups();
+                }
+            }
+            (_, KeyCode::Enter) if self.screen == Screen::Cgroups => self.filter_selected_cgroup(),
+            (_, KeyCode::Char('O')) => self.toggle_screen(Screen::Oom),
+            (_, KeyCode::Char('i')) => {
+                self.toggle_screen(Screen::Inventory);
+                if self.screen == Screen::Inventory {
+                    self.inventory = Some(Inventory::read());
+                    self.inventory_export = None;
+                }
+            }
+            (_, KeyCode::Char('x')) if self.screen == Screen::Inventory => {
+                self.export_inventory(InventoryFormat::Text);
+            }
+            (_, KeyCode::Char('X')) if self.screen == Screen::Inventory => {
+                self.export_inventory(InventoryFormat::Json);
+            }
+            (_, KeyCode::Char('p')) => {
+                self.toggle_screen(Screen::Sockets);
+                if self.screen == Screen::Sockets {
+                    self.refresh_sockets();
+                }
+            }
+            (_, KeyCode::Char(' ')) => {
+                self.paused = !self.paused;
+            }
+            (_, KeyCode::Char('a')) => {
+                self.cpu_mode = self.cpu_mode.toggle();
+            }
+            (_, KeyCode::Char('s')) => {
+                self.show_sparklines = !self.show_sparklines;
             }
             (_, KeyCode::Char('/')) => {
-                self.textarea.select_all();
-                self.textarea.delete_line_by_end();
+                let textarea = self.search_textarea();
+                textarea.select_all();
+                textarea.delete_line_by_end();
                 self.searching = !self.searching;
             }
             _ => {}
         }
     }
 
+    /// Whether the navigation keys go to the memory treemap.
+    fn is_memory_focused(&self) -> bool {
+        self.screen == Screen::Dashboard
+            && self.focus == Focus::Memory
+            && !matches!(self.memory_view, MemoryView::Chart | MemoryView::Activity)
+    }
+
+    /// Whether the navigation keys go to the disks pane.
+    fn is_disk_focused(&self) -> bool {
+        self.screen == Screen::Dashboard && self.focus == Focus::Disks
+    }
+
+    /// Whether the navigation keys go to the network pane.
+    fn is_network_focused(&self) -> bool {
+        self.screen == Screen::Dashboard && self.focus == Focus::Network
+    }
+
+    /// Returns the input area of the search in the focused pane.
+    fn search_textarea(&mut self) -> &mut TextArea<'static> {
+        if self.is_network_focused() {
+            &mut self.network_filter
+        } else {
+            &mut self.textarea
+        }
+    }
+
+    /// Returns the state of the table shown on the current screen, if it has one.
+    fn selected_table_state(&mut self) -> Option<&mut TableState> {
+        match self.screen {
+            Screen::Dashboard => Some(&mut self.table_state),
+            Screen::Events => Some(&mut self.event_table_state),
+            Screen::Suspects => Some(&mut self.suspect_table_state),
+            Screen::Ranking => Some(&mut self.ranking_table_state),
+            Screen::Sockets => Some(&mut self.socket_table_state),
+            Screen::Interfaces => Some(&mut self.interface_table_state),
+            Screen::Explorer => Some(&mut self.explorer_table_state),
+            // There is nothing to select in the pressure charts.
+            Screen::Pressure => None,
+            Screen::Cgroups => Some(&mut self.cgroup_table_state),
+            Screen::Inventory => Some(&mut self.inventory_table_state),
+            Screen::Oom => Some(&mut self.oom_table_state),
+        }
+    }
+
+    /// Switches to the given screen, or back to the dashboard if it is already shown.
+    fn toggle_screen(&mut self, screen: Screen) {
+        self.screen = if self.screen == screen {
+            Screen::Dashboard
+        } else {
+            screen
+        };
+    }
+
     /// Set running to false to quit the application.
     fn quit(&mut self) {
         self.running = false;
     }
 }
+
+/// Reads the disks along with the inode counts and mount flags of their filesystem.
+///
+/// This blocks while a network filesystem does not respond, so it is done in the
+/// background.
+fn read_disks() -> Vec<DiskData> {
+    let disks = Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .map(|disk| {
+            let stats = filesystem_stats(disk.mount_point()).unwrap_or_default();
+            let file_system = disk.file_system().to_string_lossy();
+            DiskData {
+                name: disk.name().to_string_lossy().to_string(),
+                mount_point: disk.mount_point().to_path_buf(),
+                available: disk.available_space(),
+                total: disk.total_space(),
+                full_in: None,
+                inodes: stats.inodes,
+                free_inodes: stats.free_inodes,
+                read_only: stats.read_only || disk.is_read_only(),
+                no_exec: stats.no_exec,
+                removable: disk.is_removable(),
+                remote: REMOTE_FILE_SYSTEMS.contains(&file_system.as_ref()),
+            }
+        })
+        .collect()
+}
+
+/// Fits a line to the given points with the least squares method.
+///
+/// Returns the slope and the intercept of the line.
+fn linear_fit(points: &[(f64, f64)]) -> (f64, f64) {
+    let n = points.len() as f64;
+    if n == 0.0 {
+        return (0.0, 0.0);
+    }
+    let mean_x = points.iter().map(|v| v.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|v| v.1).sum::<f64>() / n;
+    let covariance = points
+        .iter()
+        .map(|v| (v.0 - mean_x) * (v.1 - mean_y))
+        .sum::<f64>();
+    let variance = points.iter().map(|v| (v.0 - mean_x).powi(2)).sum::<f64>();
+    if variance == 0.0 {
+        return (0.0, mean_y);
+    }
+    let slope = covariance / variance;
+    (slope, mean_y - slope * mean_x)
+}
+
+/// Formats a number of bytes with a binary unit.
+fn format_bytes(bytes: u64) -> String {
+    let units = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < units.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", units[unit])
+    }
+}
+
+/// Formats a duration with its two most significant units, e.g. `3h 20m`.
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (days, hours, minutes, secs) = (secs / 86400, secs / 3600 % 24, secs / 60 % 60, secs % 60);
+    if days > 0 {
+        format!("{days}d {hours}h")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else if minutes > 0 {
+        format!("{minutes}m {secs}s")
+    } else {
+        format!("{:.1}s", duration.as_secs_f64())
+    }
+}
+
+/// Formats the time of day as `HH:MM:SS` in the local timezone.
+fn format_time(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    #[cfg(unix)]
+    {
+        let secs = secs as libc::time_t;
+        // SAFETY: `tm` is a plain C struct that `localtime_r` fills in.
+        let mut tm = unsafe { std::mem::zeroed::<libc::tm>() };
+        if !unsafe { libc::localtime_r(&secs, &mut tm) }.is_null() {
+            return format!("{:02}:{:02}:{:02}", tm.tm_hour, tm.tm_min, tm.tm_sec);
+        }
+    }
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs / 3600 % 24,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+/// Returns the exit code of ratatop for the given status of the launched command.
+///
+/// A command killed by a signal gets 128 plus the signal number, like in a shell.
+fn exit_code(status: ExitStatus) -> Option<i32> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+
+        if let Some(signal) = status.signal() {
+            return Some(128 + signal);
+        }
+    }
+    status.code()
+}
+
+/// Creates a new file only readable by the user to write the output of a launched command to.
+///
+/// The temporary directory is shared with the other users, so an existing file, or a link
+/// planted in its place, is never opened.
+fn create_log() -> Result<(fs::File, PathBuf)> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+
+        options.mode(0o600);
+    }
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    for attempt in 0..100 {
+        let name = format!("ratatop-{}-{time}-{attempt}.log", std::process::id());
+        let path = std::env::temp_dir().join(name);
+        match options.open(&path) {
+            Ok(file) => return Ok((file, path)),
+            Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(error.into()),
+        }
+    }
+    bail!(
+        "cannot create a log file in {}",
+        std::env::temp_dir().display()
+    );
+}
+
+/// Returns the CPU time in seconds of the children of ratatop that have been waited for,
+/// along with their own waited-for descendants.
+fn children_cpu_time() -> Option<f64> {
+    #[cfg(unix)]
+    {
+        // SAFETY: `usage` is a plain C struct that `getrusage` fills in.
+        let mut usage = unsafe { std::mem::zeroed::<libc::rusage>() };
+        if unsafe { libc::getrusage(libc::RUSAGE_CHILDREN, &mut usage) } != 0 {
+            return None;
+        }
+        let seconds = |time: libc::timeval| time.tv_sec as f64 + time.tv_usec as f64 / 1_000_000.0;
+        Some(seconds(usage.ru_utime) + seconds(usage.ru_stime))
+    }
+    #[cfg(not(unix))]
+    {
+        None
+    }
+}
+
+/// Parses a duration such as `90s`, `30m`, `24h` or `7d`, in seconds without a unit.
+fn parse_duration(text: &str) -> Result<Duration> {
+    let (value, unit) = match text.find(|c: char| !c.is_ascii_digit()) {
+        Some(index) => text.split_at(index),
+        None => (text, "s"),
+    };
+    let Ok(value) = value.parse::<u64>() else {
+        bail!("invalid duration: {text}");
+    };
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => bail!("invalid duration: {text}"),
+    };
+    let Some(seconds) = value.checked_mul(multiplier) else {
+        bail!("duration too long: {text}");
+    };
+    Ok(Duration::from_secs(seconds))
+}
+
+/// Reads the inode counts and mount flags of the filesystem mounted at the given path.
+fn filesystem_stats(mount_point: &Path) -> Option<FilesystemStats> {
+    #[cfg(unix)]
+    {
+        use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+        let path = CString::new(mount_point.as_os_str().as_bytes()).ok()?;
+        // SAFETY: `stat` is a plain C struct that `statvfs` fills in.
+        let mut stat = unsafe { std::mem::zeroed::<libc::statvfs>() };
+        if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+            return None;
+        }
+        Some(FilesystemStats {
+            inodes: stat.f_files as u64,
+            free_inodes: stat.f_ffree as u64,
+            read_only: stat.f_flag & libc::ST_RDONLY != 0,
+            no_exec: stat.f_flag & libc::ST_NOEXEC != 0,
+        })
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = mount_point;
+        None
+    }
+}
+
+/// Whether the name matches the space-separated glob patterns such as `eth* !veth*`.
+///
+/// Names must match any of the patterns, if any, and none of the ones negated with `!`.
+fn matches_patterns(name: &str, patterns: &str) -> bool {
+    let (excluded, included): (Vec<_>, Vec<_>) = patterns
+        .split_whitespace()
+        .partition(|pattern| pattern.starts_with('!'));
+    (included.is_empty() || included.iter().any(|pattern| matches_glob(name, pattern)))
+        && !excluded
+            .iter()
+            .any(|pattern| matches_glob(name, &pattern[1..]))
+}
+
+/// Whether the text matches the glob pattern, where `*` matches any characters and `?` a
+/// single one.
+fn matches_glob(text: &str, pattern: &str) -> bool {
+    let mut pattern_chars = pattern.chars();
+    match pattern_chars.next() {
+        None => text.is_empty(),
+        Some('*') => text
+            .char_indices()
+            .map(|(index, _)| index)
+            .chain([text.len()])
+            .any(|index| matches_glob(&text[index..], pattern_chars.as_str())),
+        Some(expected) => {
+            let mut text_chars = text.chars();
+            match text_chars.next() {
+                Some(actual) if expected == '?' || expected == actual => {
+                    matches_glob(text_chars.as_str(), pattern_chars.as_str())
+                }
+                _ => false,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses the arguments that follow the program name.
+    fn parse_args(args: &[&str]) -> Result<Args> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_commands() {
+        let args = parse_args(&["--", "cargo", "build", "--release"]).unwrap();
+        assert_eq!(args.command, ["cargo", "build", "--release"]);
+        // Options after `--` belong to the command.
+        let args = parse_args(&["--disk-horizon", "1h", "--", "ls", "--pid", "1"]).unwrap();
+        assert_eq!(args.command, ["ls", "--pid", "1"]);
+        assert_eq!(args.disk_horizon, Some(Duration::from_secs(60 * 60)));
+        assert!(args.pid.is_none());
+        assert!(parse_args(&[]).unwrap().command.is_empty());
+        assert!(parse_args(&["cargo"]).is_err());
+    }
+
+    #[test]
+    fn reports_the_busiest_processes() {
+        let mut app = App::new();
+        app.launch(&["sleep".to_string(), "10".to_string()])
+            .unwrap();
+        let command = app.command.as_mut().unwrap();
+        let log_path = command.log_path.clone();
+        for (pid, cpu_time, reaped_in_tree) in (1..=7).map(|i| (i, i as f64, i % 2 == 0)) {
+            let usage = TreeUsage {
+                name: format!("child{pid}"),
+                cpu_time,
+                read_bytes: 10,
+                written_bytes: 100,
+                reaped_in_tree,
+                ..TreeUsage::default()
+            };
+            command.usage.insert(Pid::from_u32(pid), usage);
+        }
+
+        let report = app.finish_command().unwrap().unwrap();
+        fs::remove_file(&log_path).unwrap();
+        let busiest = report
+            .busiest
+            .iter()
+            .map(|(_, usage)| usage.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(busiest, ["child7", "child6", "child5", "child4", "child3"]);
+        // The I/O of the processes reaped in the tree is counted by their parent.
+        assert_eq!(report.read_bytes, 40);
+        assert_eq!(report.written_bytes, 400);
+        // The command is killed once ratatop quits.
+        assert_eq!(report.status.and_then(exit_code), Some(128 + libc::SIGKILL));
+        assert_eq!(report.log_path, log_path);
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration("90s").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration("30m").unwrap(), Duration::from_secs(30 * 60));
+        assert_eq!(
+            parse_duration("24h").unwrap(),
+            Duration::from_secs(24 * 60 * 60)
+        );
+        assert_eq!(
+            parse_duration("7d").unwrap(),
+            Duration::from_secs(7 * 24 * 60 * 60)
+        );
+    }
+
+    #[test]
+    fn parses_pids() {
+        assert_eq!(
+            parse_args(&["--pid", "42"]).unwrap().pid,
+            Some(Pid::from(42))
+        );
+        assert_eq!(parse_args(&["-p", "1"]).unwrap().pid, Some(Pid::from(1)));
+        let error = parse_args(&["--pid"]).unwrap_err();
+        assert_eq!(error.to_string(), "missing value for --pid");
+        assert!(parse_args(&["--pid", "init"]).is_err());
+        let error = parse_args(&["--pid", "1", "--", "sleep", "1"]).unwrap_err();
+        assert_eq!(error.to_string(), "--pid cannot be combined with a command");
+    }
+
+    #[test]
+    fn rejects_invalid_durations() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("d").is_err());
+        assert!(parse_duration("-1h").is_err());
+        assert!(parse_duration("1.5h").is_err());
+        assert!(parse_duration("3w").is_err());
+        assert!(parse_duration("999999999999999d").is_err());
+        assert!(parse_duration("99999999999999999999").is_err());
+    }
+
+    #[test]
+    fn fits_lines() {
+        let points = [(0.0, 1.0), (1.0, 3.0), (2.0, 5.0), (3.0, 7.0)];
+        assert_eq!(linear_fit(&points), (2.0, 1.0));
+
+        // Noise around the line is averaged out.
+        let (slope, intercept) = linear_fit(&[(0.0, 0.0), (1.0, 2.0), (2.0, 0.0), (3.0, 2.0)]);
+        assert!((slope - 0.4).abs() < 1e-9);
+        assert!((intercept - 0.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn fits_memory_trends_per_second() {
+        let start = Instant::now();
+        let samples = [0, 30, 60, 90].map(|seconds| MemorySample {
+            time: start + Duration::from_secs(seconds),
+            memory: 1_000_000 + seconds * 1000,
+        });
+        let points = App::memory_trend_points(&samples);
+        assert_eq!(points[0], (0.0, 1_000_000.0));
+        assert_eq!(points[3], (90.0, 1_090_000.0));
+        let (slope, intercept) = linear_fit(&points);
+        assert!((slope - 1000.0).abs() < 1e-6);
+        assert!((intercept - 1_000_000.0).abs() < 1e-6);
+        assert!(App::memory_trend_points(&[]).is_empty());
+    }
+
+    #[test]
+    fn fits_degenerate_lines() {
+        assert_eq!(linear_fit(&[]), (0.0, 0.0));
+        assert_eq!(linear_fit(&[(5.0, 3.0)]), (0.0, 3.0));
+        assert_eq!(linear_fit(&[(5.0, 1.0), (5.0, 3.0)]), (0.0, 2.0));
+    }
+
+    #[test]
+    fn logs_spawned_and_exited_processes() {
+        let mut app = App::new();
+        let own_pid = Pid::from_u32(std::process::id());
+        // The processes running at startup are not logged.
+        app.record_process_events(HashSet::from([own_pid]));
+        assert!(app.process_events.is_empty());
+        assert!(app.process_summaries.contains_key(&own_pid));
+
+        let mut child = std::process::Command::new("sleep")
+            .arg("10")
+            .spawn()
+            .unwrap();
+        let child_pid = Pid::from_u32(child.id());
+        app.record_process_events(HashSet::from([own_pid, child_pid]));
+        child.kill().unwrap();
+        child.wait().unwrap();
+        assert_eq!(app.process_events.len(), 1);
+        let event = &app.process_events[0];
+        assert_eq!(event.kind, ProcessEventKind::Spawned);
+        assert_eq!(event.pid, child_pid);
+        assert_eq!(event.summary.name, "sleep");
+        assert_eq!(event.summary.parent, Some(own_pid));
+
+        app.record_process_events(HashSet::from([own_pid]));
+        assert_eq!(app.process_events.len(), 2);
+        let event = &app.process_events[1];
+        assert_eq!(event.kind, ProcessEventKind::Exited);
+        assert_eq!(event.pid, child_pid);
+        assert_eq!(event.summary.name, "sleep");
+        assert!(!app.process_summaries.contains_key(&child_pid));
+
+        // Nothing is logged while the processes do not change.
+        app.record_process_events(HashSet::from([own_pid]));
+        assert_eq!(app.process_events.len(), 2);
+    }
+
+    #[test]
+    fn parses_filter_conditions() {
+        let condition = FilterCondition::parse("cpu>50").unwrap();
+        assert_eq!(condition.column, 2);
+        assert_eq!(condition.ordering, std::cmp::Ordering::Greater);
+        assert!(!condition.or_equal);
+        assert_eq!(condition.value, 50.0);
+
+        let condition = FilterCondition::parse("MEM<=1.5").unwrap();
+        assert_eq!(condition.column, 3);
+        assert_eq!(condition.ordering, std::cmp::Ordering::Less);
+        assert!(condition.or_equal);
+        assert_eq!(condition.value, 1.5);
+
+        assert!(FilterCondition::parse("firefox").is_none());
+        assert!(FilterCondition::parse("io>5").is_none());
+        assert!(FilterCondition::parse("cpu>").is_none());
+        assert!(FilterCondition::parse("cpu>high").is_none());
+    }
+
+    #[test]
+    fn matches_filter_conditions() {
+        let row = ["1", "firefox", "50.00", "2.50"].map(str::to_string);
+        let matches = |term: &str| FilterCondition::parse(term).unwrap().matches(&row);
+        assert!(matches("cpu>40"));
+        assert!(!matches("cpu>50"));
+        assert!(matches("cpu>=50"));
+        assert!(matches("mem<3"));
+        assert!(!matches("mem<2.5"));
+        assert!(matches("mem<=2.5"));
+        // Cells that are not numbers never match.
+        let blank_row = ["1", "firefox", "", ""].map(str::to_string);
+        assert!(!FilterCondition::parse("mem>=0")
+            .unwrap()
+            .matches(&blank_row));
+    }
+
+    #[test]
+    fn matches_globs() {
+        assert!(matches_glob("eth0", "eth0"));
+        assert!(matches_glob("eth0", "eth*"));
+        assert!(matches_glob("eth0", "*"));
+        assert!(matches_glob("", "*"));
+        assert!(matches_glob("eth0", "e?h?"));
+        assert!(matches_glob("veth1a2b", "*th*b"));
+        assert!(!matches_glob("eth0", "eth"));
+        assert!(!matches_glob("eth0", "eth?0"));
+        assert!(!matches_glob("veth0", "eth*"));
+        assert!(!matches_glob("", "?"));
+    }
+
+    #[test]
+    fn matches_included_and_excluded_patterns() {
+        assert!(matches_patterns("eth0", ""));
+        assert!(matches_patterns("eth0", "eth* wlan*"));
+        assert!(matches_patterns("wlan0", "eth* wlan*"));
+        assert!(!matches_patterns("lo", "eth* wlan*"));
+        assert!(matches_patterns("eth0", "!veth*"));
+        assert!(!matches_patterns("veth0", "!veth*"));
+        assert!(!matches_patterns("eth1", "eth* !eth1"));
+    }
+
+    #[test]
+    fn hides_interfaces_by_link() {
+        let mut app = App::new();
+        let details = |link: Link| InterfaceDetails {
+            link,
+            time: Instant::now(),
+            receive_rate: 0.0,
+            transmit_rate: 0.0,
+            error_rate: 0.0,
+            drop_rate: 0.0,
+            collision_rate: 0.0,
+        };
+        app.interfaces = HashMap::from([
+            (
+                "eth0".to_string(),
+                details(Link {
+                    operstate: "up".to_string(),
+                    ..Link::default()
+                }),
+            ),
+            (
+                "veth0".to_string(),
+                details(Link {
+                    virtual_device: true,
+                    ..Link::default()
+                }),
+            ),
+        ]);
+        // Without toggles, interfaces are shown whether or not their link is known.
+        assert!(app.is_network_shown("veth0"));
+        assert!(app.is_network_shown("veth1"));
+
+        app.hide_virtual = true;
+        assert!(app.is_network_shown("eth0"));
+        assert!(!app.is_network_shown("veth0"));
+        assert!(!app.is_network_shown("veth1"));
+    }
+
+    #[test]
+    fn keeps_the_process_selection_on_the_pressure_screen() {
+        let mut app = App::new();
+        app.table_state.select(Some(3));
+        app.screen = Screen::Pressure;
+        app.on_key_event(KeyEvent::from(KeyCode::Char('j')));
+        app.on_key_event(KeyEvent::from(KeyCode::Down));
+        assert_eq!(app.table_state.selected(), Some(3));
+
+        app.screen = Screen::Dashboard;
+        app.on_key_event(KeyEvent::from(KeyCode::Char('j')));
+        assert_eq!(app.table_state.selected(), Some(4));
+    }
+}
@@ -0,0 +1,116 @@
+//! Pressure Stall Information read from `/proc/pressure`.
+
+use std::{fmt, fs};
+
+/// A resource that tasks can be stalled on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Resource {
+    Cpu,
+    Memory,
+    Io,
+}
+
+impl Resource {
+    pub const ALL: [Self; 3] = [Self::Cpu, Self::Memory, Self::Io];
+
+    fn path(self) -> &'static str {
+        match self {
+            Self::Cpu => "/proc/pressure/cpu",
+            Self::Memory => "/proc/pressure/memory",
+            Self::Io => "/proc/pressure/io",
+        }
+    }
+}
+
+impl fmt::Display for Resource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Cpu => "CPU",
+            Self::Memory => "Memory",
+            Self::Io => "I/O",
+        })
+    }
+}
+
+/// Share of time stalled, in percent, averaged over 10, 60 and 300 seconds.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Averages {
+    pub avg10: f64,
+    pub avg60: f64,
+    pub avg300: f64,
+}
+
+/// The pressure on a resource.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Pressure {
+    /// Time during which some tasks were stalled.
+    pub some: Averages,
+    /// Time during which all non-idle tasks were stalled at once.
+    pub full: Averages,
+}
+
+/// Reads the pressure on the given resource.
+///
+/// Returns `None` when the kernel was built without PSI or booted with `psi=0`.
+pub fn read_pressure(resource: Resource) -> Option<Pressure> {
+    parse(&fs::read_to_string(resource.path()).ok()?)
+}
+
+/// Parses the content of a pressure file.
+fn parse(content: &str) -> Option<Pressure> {
+    let mut pressure = Pressure::default();
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let averages = match fields.next()? {
+            "some" => &mut pressure.some,
+            // Older kernels do not report `full` for the CPU.
+            "full" => &mut pressure.full,
+            _ => continue,
+        };
+        for field in fields {
+            let Some((key, value)) = field.split_once('=') else {
+                continue;
+            };
+            let value = value.parse().ok()?;
+            match key {
+                "avg10" => averages.avg10 = value,
+                "avg60" => averages.avg60 = value,
+                "avg300" => averages.avg300 = value,
+                _ => {}
+            }
+        }
+    }
+    Some(pressure)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_some_and_full() {
+        let content = "some avg10=1.53 avg60=0.87 avg300=0.29 total=20464366\n\
+            full avg10=0.50 avg60=0.25 avg300=0.08 total=9870912\n";
+        let pressure = parse(content).unwrap();
+        assert_eq!(pressure.some.avg10, 1.53);
+        assert_eq!(pressure.some.avg60, 0.87);
+        assert_eq!(pressure.some.avg300, 0.29);
+        assert_eq!(pressure.full.avg10, 0.50);
+        assert_eq!(pressure.full.avg60, 0.25);
+        assert_eq!(pressure.full.avg300, 0.08);
+    }
+
+    #[test]
+    fn leaves_full_empty_on_older_kernels() {
+        let pressure = parse("some avg10=12.00 avg60=4.10 avg300=1.00 total=3187221\n").unwrap();
+        assert_eq!(pressure.some.avg10, 12.0);
+        assert_eq!(pressure.full.avg10, 0.0);
+        assert_eq!(pressure.full.avg300, 0.0);
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        assert!(parse("some avg10=high avg60=0.00 avg300=0.00 total=0\n").is_none());
+        assert!(parse("\n").is_none());
+    }
+}
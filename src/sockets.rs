@@ -0,0 +1,378 @@
+//! Sockets read from `/proc/<pid>/net` and their owning processes.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt, fs,
+    net::{Ipv4Addr, Ipv6Addr},
+};
+
+use sysinfo::Pid;
+
+/// The protocol of a socket.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Protocol {
+    Tcp,
+    Tcp6,
+    Udp,
+    Udp6,
+    Unix,
+}
+
+impl Protocol {
+    const ALL: [Self; 5] = [Self::Tcp, Self::Tcp6, Self::Udp, Self::Udp6, Self::Unix];
+
+    /// The file in `/proc/<pid>/net` that lists the sockets of this protocol.
+    fn file_name(self) -> &'static str {
+        match self {
+            Self::Tcp => "tcp",
+            Self::Tcp6 => "tcp6",
+            Self::Udp => "udp",
+            Self::Udp6 => "udp6",
+            Self::Unix => "unix",
+        }
+    }
+
+    fn is_tcp(self) -> bool {
+        matches!(self, Self::Tcp | Self::Tcp6)
+    }
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Tcp => "tcp",
+            Self::Tcp6 => "tcp6",
+            Self::Udp => "udp",
+            Self::Udp6 => "udp6",
+            Self::Unix => "unix",
+        })
+    }
+}
+
+/// A socket listed in `/proc/net`.
+#[derive(Clone, Debug)]
+pub struct Socket {
+    pub protocol: Protocol,
+    /// The local address and port, or the path of a unix socket.
+    pub local_address: String,
+    pub remote_address: String,
+    /// The state as listed in `/proc/net`, see [`Socket::state_name`].
+    pub state: u8,
+    pub send_queue: u64,
+    pub receive_queue: u64,
+    pub inode: u64,
+    /// Whether a unix socket accepts connections.
+    accepts_connections: bool,
+}
+
+impl Socket {
+    /// Whether the socket waits for connections or datagrams.
+    pub fn is_listening(&self) -> bool {
+        match self.protocol {
+            Protocol::Tcp | Protocol::Tcp6 => self.state == TCP_LISTEN,
+            // Bound but unconnected.
+            Protocol::Udp | Protocol::Udp6 => self.state == TCP_CLOSE,
+            Protocol::Unix => self.accepts_connections,
+        }
+    }
+
+    /// The name of the state, e.g. `ESTABLISHED`.
+    pub fn state_name(&self) -> &'static str {
+        if !self.protocol.is_tcp() {
+            return match (self.protocol, self.state) {
+                (Protocol::Unix, _) if self.accepts_connections => "LISTEN",
+                (Protocol::Unix, 3) => "CONNECTED",
+                (Protocol::Unix, _) => "UNCONNECTED",
+                (_, TCP_CLOSE) => "UNCONN",
+                _ => "ESTABLISHED",
+            };
+        }
+        match self.state {
+            0x01 => "ESTABLISHED",
+            0x02 => "SYN_SENT",
+            0x03 => "SYN_RECV",
+            0x04 => "FIN_WAIT1",
+            0x05 => "FIN_WAIT2",
+            TCP_TIME_WAIT => "TIME_WAIT",
+            TCP_CLOSE => "CLOSE",
+            0x08 => "CLOSE_WAIT",
+            0x09 => "LAST_ACK",
+            TCP_LISTEN => "LISTEN",
+            0x0B => "CLOSING",
+            _ => "UNKNOWN",
+        }
+    }
+
+    /// The port of the local address, if any.
+    pub fn local_port(&self) -> Option<u16> {
+        self.local_address.rsplit_once(':')?.1.parse().ok()
+    }
+
+    /// Whether the socket is bound to all the addresses, e.g. `0.0.0.0:80` or `[::]:80`.
+    fn is_bound_to_any(&self) -> bool {
+        self.local_address
+            .rsplit_once(':')
+            .is_some_and(|(ip, _)| matches!(ip, "0.0.0.0" | "[::]"))
+    }
+}
+
+const TCP_TIME_WAIT: u8 = 0x06;
+const TCP_CLOSE: u8 = 0x07;
+const TCP_LISTEN: u8 = 0x0A;
+
+/// Flag of a unix socket that accepts connections (`__SO_ACCEPTCON`).
+const UNIX_ACCEPT_CONNECTIONS: u32 = 0x10000;
+
+/// Reads the sockets of all the protocols in the network namespaces of ratatop and of the
+/// given processes.
+///
+/// Each namespace is read once, through the first of its processes. The namespaces of
+/// processes owned by other users can only be read with enough privileges.
+pub fn read_sockets(pids: impl IntoIterator<Item = Pid>) -> Vec<Socket> {
+    let mut namespaces = HashSet::new();
+    std::iter::once(Pid::from_u32(std::process::id()))
+        .chain(pids)
+        .filter(|pid| net_namespace(*pid).is_some_and(|namespace| namespaces.insert(namespace)))
+        .flat_map(|pid| read_tables(pid, &Protocol::ALL))
+        .collect()
+}
+
+/// Reads the sockets of the given protocols in the network namespace of the given process.
+///
+/// Protocols that are not available on the running system are skipped.
+fn read_tables(pid: Pid, protocols: &[Protocol]) -> Vec<Socket> {
+    protocols
+        .iter()
+        .flat_map(|protocol| {
+            let path = format!("/proc/{pid}/net/{}", protocol.file_name());
+            let content = fs::read_to_string(path).unwrap_or_default();
+            content
+                .lines()
+                .skip(1)
+                .filter_map(|line| match protocol {
+                    Protocol::Unix => parse_unix_socket(line),
+                    _ => parse_inet_socket(*protocol, line),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Returns the inode of the network namespace of the given process, e.g. `4026531840`.
+fn net_namespace(pid: Pid) -> Option<u64> {
+    let link = fs::read_link(format!("/proc/{pid}/ns/net")).ok()?;
+    let link = link.to_str()?;
+    link.strip_prefix("net:[")?.strip_suffix(']')?.parse().ok()
+}
+
+/// Parses a line of `/proc/net/{tcp,udp}{,6}`.
+fn parse_inet_socket(protocol: Protocol, line: &str) -> Option<Socket> {
+    let fields = line.split_whitespace().collect::<Vec<_>>();
+    let (send_queue, receive_queue) = fields.get(4)?.split_once(':')?;
+    Some(Socket {
+        protocol,
+        local_address: parse_address(fields.get(1)?)?,
+        remote_address: parse_address(fields.get(2)?)?,
+        state: u8::from_str_radix(fields.get(3)?, 16).ok()?,
+        send_queue: u64::from_str_radix(send_queue, 16).ok()?,
+        receive_queue: u64::from_str_radix(receive_queue, 16).ok()?,
+        inode: fields.get(9)?.parse().ok()?,
+        accepts_connections: false,
+    })
+}
+
+/// Parses a line of `/proc/net/unix`.
+fn parse_unix_socket(line: &str) -> Option<Socket> {
+    let fields = line.split_whitespace().collect::<Vec<_>>();
+    let flags = u32::from_str_radix(fields.get(3)?, 16).ok()?;
+    Some(Socket {
+        protocol: Protocol::Unix,
+        local_address: fields.get(7).unwrap_or(&"").to_string(),
+        remote_address: String::new(),
+        state: u8::from_str_radix(fields.get(5)?, 16).ok()?,
+        send_queue: 0,
+        receive_queue: 0,
+        inode: fields.get(6)?.parse().ok()?,
+        accepts_connections: flags & UNIX_ACCEPT_CONNECTIONS != 0,
+    })
+}
+
+/// Parses an address such as `0100007F:1F90` into `127.0.0.1:8080`.
+///
+/// The address is made of 32-bit words in host byte order.
+fn parse_address(address: &str) -> Option<String> {
+    let (ip, port) = address.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let words = (0..ip.len() / 8)
+        .map(|i| u32::from_str_radix(&ip[i * 8..i * 8 + 8], 16))
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+    match words.as_slice() {
+        [word] => Some(format!("{}:{port}", Ipv4Addr::from(word.to_ne_bytes()))),
+        [_, _, _, _] => {
+            let bytes = words
+                .iter()
+                .flat_map(|word| word.to_ne_bytes())
+                .collect::<Vec<_>>();
+            let ip = Ipv6Addr::from(<[u8; 16]>::try_from(bytes).ok()?);
+            Some(format!("[{ip}]:{port}"))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the inodes of the sockets opened by the given process.
+///
+/// The file descriptors of processes owned by other users can only be read with enough
+/// privileges, otherwise nothing is returned.
+pub fn socket_inodes(pid: Pid) -> HashSet<u64> {
+    let Ok(entries) = fs::read_dir(format!("/proc/{pid}/fd")) else {
+        return HashSet::new();
+    };
+    entries
+        .filter_map(|entry| {
+            let target = fs::read_link(entry.ok()?.path()).ok()?;
+            let target = target.to_str()?;
+            target
+                .strip_prefix("socket:[")?
+                .strip_suffix(']')?
+                .parse()
+                .ok()
+        })
+        .collect()
+}
+
+/// Returns the processes owning each socket inode, in ascending order.
+///
+/// A socket is shared by several processes when it is inherited, e.g. by the workers of a
+/// pre-forking server.
+pub fn socket_owners(pids: impl IntoIterator<Item = Pid>) -> HashMap<u64, Vec<Pid>> {
+    let mut owners = HashMap::<u64, Vec<Pid>>::new();
+    for pid in pids {
+        for inode in socket_inodes(pid) {
+            owners.entry(inode).or_default().push(pid);
+        }
+    }
+    for pids in owners.values_mut() {
+        pids.sort();
+    }
+    owners
+}
+
+/// Returns the TCP and UDP sockets opened by the given process, in its network namespace.
+///
+/// Sockets in `TIME_WAIT` are no longer owned by a process, so they are returned when they
+/// have the local address of one of its sockets, such as the port it listens on.
+pub fn process_connections(pid: Pid) -> Vec<Socket> {
+    let inodes = socket_inodes(pid);
+    if inodes.is_empty() {
+        return Vec::new();
+    }
+    let protocols = [Protocol::Tcp, Protocol::Tcp6, Protocol::Udp, Protocol::Udp6];
+    let (mut connections, time_wait): (Vec<_>, Vec<_>) = read_tables(pid, &protocols)
+        .into_iter()
+        .filter(|socket| inodes.contains(&socket.inode) || socket.state == TCP_TIME_WAIT)
+        .partition(|socket| socket.inode != 0);
+    let time_wait = time_wait
+        .into_iter()
+        .filter(|closed| {
+            connections.iter().any(|socket| {
+                socket.protocol == closed.protocol
+                    && (socket.local_address == closed.local_address
+                        || socket.is_listening()
+                            && socket.is_bound_to_any()
+                            && socket.local_port() == closed.local_port())
+            })
+        })
+        .collect::<Vec<_>>();
+    connections.extend(time_wait);
+    connections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lines of `/proc/net/tcp6` for a server listening on port 18083, and a connection to it
+    /// from `::1` that the server closed.
+    const LISTENING: &str = "   0: 00000000000000000000000000000000:46A3 \
+        00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000     0 \
+        0 129976 1 000000000eeb1d7b 100 0 0 10 0";
+    const TIME_WAIT: &str = "   1: 00000000000000000000000001000000:46A3 \
+        00000000000000000000000001000000:E114 06 00000000:00000000 03:00001752 00000000     0 \
+        0 0 3 0000000036fbbcc5";
+    const ESTABLISHED: &str = "   2: 00000000000000000000000001000000:46A2 \
+        00000000000000000000000001000000:B200 01 00000000:00000005 00:00000000 00000000     0 \
+        0 129911 1 00000000717d2b35 20 4 30 10 -1";
+
+    #[test]
+    fn parses_ipv4_addresses() {
+        assert_eq!(
+            parse_address("0100007F:1F90").as_deref(),
+            Some("127.0.0.1:8080")
+        );
+        assert_eq!(parse_address("00000000:0000").as_deref(), Some("0.0.0.0:0"));
+    }
+
+    #[test]
+    fn parses_ipv6_addresses() {
+        assert_eq!(
+            parse_address("00000000000000000000000001000000:46A3").as_deref(),
+            Some("[::1]:18083")
+        );
+        assert_eq!(
+            parse_address("0000000000000000FFFF00000100007F:0050").as_deref(),
+            Some("[::ffff:127.0.0.1]:80")
+        );
+        assert_eq!(
+            parse_address("B80D0120000000000000000001000000:01BB").as_deref(),
+            Some("[2001:db8::1]:443")
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_addresses() {
+        assert_eq!(parse_address("0100007F"), None);
+        assert_eq!(parse_address("0100007G:1F90"), None);
+        assert_eq!(parse_address("0100:1F90"), None);
+    }
+
+    #[test]
+    fn parses_listening_sockets() {
+        let socket = parse_inet_socket(Protocol::Tcp6, LISTENING).unwrap();
+        assert_eq!(socket.local_address, "[::]:18083");
+        assert_eq!(socket.remote_address, "[::]:0");
+        assert_eq!(socket.state_name(), "LISTEN");
+        assert!(socket.is_listening());
+        assert!(socket.is_bound_to_any());
+        assert_eq!(socket.local_port(), Some(18083));
+        assert_eq!(socket.inode, 129976);
+    }
+
+    #[test]
+    fn parses_connected_sockets() {
+        let socket = parse_inet_socket(Protocol::Tcp6, ESTABLISHED).unwrap();
+        assert_eq!(socket.local_address, "[::1]:18082");
+        assert_eq!(socket.remote_address, "[::1]:45568");
+        assert_eq!(socket.state_name(), "ESTABLISHED");
+        assert!(!socket.is_listening());
+        assert_eq!((socket.send_queue, socket.receive_queue), (0, 5));
+        assert_eq!(socket.inode, 129911);
+    }
+
+    #[test]
+    fn parses_time_wait_sockets_without_inode() {
+        let socket = parse_inet_socket(Protocol::Tcp6, TIME_WAIT).unwrap();
+        assert_eq!(socket.local_address, "[::1]:18083");
+        assert_eq!(socket.remote_address, "[::1]:57620");
+        assert_eq!(socket.state_name(), "TIME_WAIT");
+        assert_eq!(socket.inode, 0);
+    }
+
+    #[test]
+    fn rejects_the_header() {
+        let header = "  sl  local_address                         remote_address                \
+            st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode";
+        assert!(parse_inet_socket(Protocol::Tcp6, header).is_none());
+    }
+}
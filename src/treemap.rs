@@ -0,0 +1,179 @@
+//! Squarified treemap layout.
+//!
+//! See Bruls, Huizing and van Wijk, "Squarified Treemaps".
+
+use ratatui::layout::Rect;
+
+/// Terminal cells are about twice as high as they are wide.
+const CELL_ASPECT_RATIO: f64 = 2.0;
+
+/// A rectangle with fractional coordinates.
+#[derive(Clone, Copy, Debug)]
+struct Area {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+/// Splits the area into one cell per value, with sizes proportional to the values.
+///
+/// The values must be sorted from largest to smallest. The cells are laid out so that
+/// they look as square as possible, which may leave small values with empty cells.
+pub fn squarify(values: &[f64], area: Rect) -> Vec<Rect> {
+    let total = values.iter().sum::<f64>();
+    if total <= 0.0 || area.is_empty() {
+        return vec![Rect::default(); values.len()];
+    }
+    // Lay out in square units so that the cells look square on screen.
+    let mut remaining = Area {
+        x: 0.0,
+        y: 0.0,
+        width: area.width as f64,
+        height: area.height as f64 * CELL_ASPECT_RATIO,
+    };
+    let scale = remaining.width * remaining.height / total;
+    let sizes = values.iter().map(|v| v * scale).collect::<Vec<_>>();
+
+    let mut cells = Vec::with_capacity(values.len());
+    let mut start = 0;
+    while start < sizes.len() {
+        let side = remaining.width.min(remaining.height);
+        let mut end = start + 1;
+        while end < sizes.len()
+            && worst(&sizes[start..=end], side) <= worst(&sizes[start..end], side)
+        {
+            end += 1;
+        }
+        remaining = layout_row(&sizes[start..end], remaining, &mut cells);
+        start = end;
+    }
+
+    cells.into_iter().map(|cell| to_rect(cell, area)).collect()
+}
+
+/// Returns the worst aspect ratio of the row laid out along a side of the given length.
+fn worst(row: &[f64], side: f64) -> f64 {
+    let sum = row.iter().sum::<f64>();
+    let (min, max) = row.iter().fold((f64::MAX, f64::MIN), |(min, max), v| {
+        (min.min(*v), max.max(*v))
+    });
+    if sum <= 0.0 || min <= 0.0 {
+        return f64::MAX;
+    }
+    let side = side * side;
+    let sum = sum * sum;
+    (side * max / sum).max(sum / (side * min))
+}
+
+/// Lays out the row along the shorter side of the area and returns what is left of it.
+fn layout_row(row: &[f64], area: Area, cells: &mut Vec<Area>) -> Area {
+    let sum = row.iter().sum::<f64>();
+    if area.width >= area.height {
+        // A column on the left.
+        let width = if area.height > 0.0 {
+            sum / area.height
+        } else {
+            0.0
+        };
+        let mut y = area.y;
+        for size in row {
+            let height = if width > 0.0 { size / width } else { 0.0 };
+            cells.push(Area {
+                x: area.x,
+                y,
+                width,
+                height,
+            });
+            y += height;
+        }
+        Area {
+            x: area.x + width,
+            width: area.width - width,
+            ..area
+        }
+    } else {
+        // A row at the top.
+        let height = if area.width > 0.0 {
+            sum / area.width
+        } else {
+            0.0
+        };
+        let mut x = area.x;
+        for size in row {
+            let width = if height > 0.0 { size / height } else { 0.0 };
+            cells.push(Area {
+                x,
+                y: area.y,
+                width,
+                height,
+            });
+            x += width;
+        }
+        Area {
+            y: area.y + height,
+            height: area.height - height,
+            ..area
+        }
+    }
+}
+
+/// Converts a cell in square units to terminal cells of the given area.
+///
+/// The edges are rounded so that neighbouring cells neither overlap nor leave gaps.
+fn to_rect(cell: Area, area: Rect) -> Rect {
+    let left = cell.x.round() as u16;
+    let right = (cell.x + cell.width).round() as u16;
+    let top = (cell.y / CELL_ASPECT_RATIO).round() as u16;
+    let bottom = ((cell.y + cell.height) / CELL_ASPECT_RATIO).round() as u16;
+    Rect {
+        x: area.x + left.min(area.width),
+        y: area.y + top.min(area.height),
+        width: right.min(area.width).saturating_sub(left),
+        height: bottom.min(area.height).saturating_sub(top),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns whether the two rectangles share at least one cell.
+    fn overlap(a: Rect, b: Rect) -> bool {
+        a.x < b.right() && b.x < a.right() && a.y < b.bottom() && b.y < a.bottom()
+    }
+
+    #[test]
+    fn tiles_the_whole_area() {
+        let area = Rect::new(3, 2, 40, 12);
+        let cells = squarify(&[6.0, 6.0, 4.0, 3.0, 2.0, 2.0, 1.0], area);
+        assert_eq!(cells.len(), 7);
+        for (i, cell) in cells.iter().enumerate() {
+            assert_eq!(
+                cell.intersection(area),
+                *cell,
+                "{cell:?} is outside the area"
+            );
+            for other in &cells[i + 1..] {
+                assert!(!overlap(*cell, *other), "{cell:?} overlaps {other:?}");
+            }
+        }
+        let covered = cells.iter().map(|cell| cell.area()).sum::<u32>();
+        assert_eq!(covered, area.area());
+    }
+
+    #[test]
+    fn sizes_cells_by_value() {
+        let cells = squarify(&[3.0, 1.0], Rect::new(0, 0, 40, 10));
+        assert_eq!(cells[0].area(), 300);
+        assert_eq!(cells[1].area(), 100);
+    }
+
+    #[test]
+    fn lays_out_nothing_without_values_or_space() {
+        let empty = vec![Rect::default(); 2];
+        assert_eq!(squarify(&[0.0, 0.0], Rect::new(0, 0, 10, 10)), empty);
+        assert_eq!(squarify(&[1.0, 2.0], Rect::new(5, 5, 0, 10)), empty);
+        assert!(squarify(&[], Rect::new(0, 0, 10, 10)).is_empty());
+    }
+}
@@ -0,0 +1,152 @@
+//! Paging, swapping and reclaim counters read from `/proc/vmstat`.
+
+use std::fs;
+
+/// Cumulative counters since boot.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Counters {
+    /// Bytes paged in from and out to disk, including swap.
+    pub page_in: u64,
+    pub page_out: u64,
+    /// Bytes swapped in and out.
+    pub swap_in: u64,
+    pub swap_out: u64,
+    /// Page faults that needed to read from disk.
+    pub major_faults: u64,
+    /// Bytes reclaimed by kswapd and by allocations.
+    pub reclaimed: u64,
+    /// Processes killed by the OOM killer, either system-wide or in a cgroup.
+    pub oom_kills: u64,
+}
+
+/// The change of the counters per second.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Rates {
+    pub page_in: f64,
+    pub page_out: f64,
+    pub swap_in: f64,
+    pub swap_out: f64,
+    pub major_faults: f64,
+    pub reclaimed: f64,
+}
+
+impl Counters {
+    /// Returns the rates of the counters since the previous ones, read the given number of
+    /// seconds earlier.
+    pub fn rates_since(&self, previous: &Self, elapsed: f64) -> Rates {
+        let rate = |current: u64, previous: u64| current.saturating_sub(previous) as f64 / elapsed;
+        Rates {
+            page_in: rate(self.page_in, previous.page_in),
+            page_out: rate(self.page_out, previous.page_out),
+            swap_in: rate(self.swap_in, previous.swap_in),
+            swap_out: rate(self.swap_out, previous.swap_out),
+            major_faults: rate(self.major_faults, previous.major_faults),
+            reclaimed: rate(self.reclaimed, previous.reclaimed),
+        }
+    }
+}
+
+/// Reads the counters, or returns `None` if `/proc/vmstat` is not available.
+pub fn read_counters() -> Option<Counters> {
+    let content = fs::read_to_string("/proc/vmstat").ok()?;
+    Some(parse(&content, page_size()))
+}
+
+/// Parses the content of `/proc/vmstat`, given the size of a page in bytes.
+fn parse(content: &str, page_size: u64) -> Counters {
+    let mut counters = Counters::default();
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once(' ') else {
+            continue;
+        };
+        let Ok(value) = value.parse::<u64>() else {
+            continue;
+        };
+        match key {
+            // Paging is counted in KiB and the rest in pages.
+            "pgpgin" => counters.page_in = value * 1024,
+            "pgpgout" => counters.page_out = value * 1024,
+            "pswpin" => counters.swap_in = value * page_size,
+            "pswpout" => counters.swap_out = value * page_size,
+            "pgmajfault" => counters.major_faults = value,
+            "oom_kill" => counters.oom_kills = value,
+            // Newer kernels also split the reclaimed pages into `pgsteal_anon` and
+            // `pgsteal_file`, which would count them twice.
+            "pgsteal_kswapd" | "pgsteal_direct" | "pgsteal_khugepaged" => {
+                counters.reclaimed += value * page_size
+            }
+            _ => {}
+        }
+    }
+    counters
+}
+
+#[cfg(unix)]
+fn page_size() -> u64 {
+    // SAFETY: `sysconf` has no preconditions.
+    let size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    u64::try_from(size).unwrap_or(4096)
+}
+
+#[cfg(not(unix))]
+fn page_size() -> u64 {
+    4096
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An excerpt of `/proc/vmstat` on a kernel that also splits the reclaim by page type.
+    const VMSTAT: &str = "nr_free_pages 1519864
+pgpgin 2048
+pgpgout 4096
+pswpin 3
+pswpout 5
+pgfault 98765
+pgmajfault 42
+pgsteal_kswapd 100
+pgsteal_direct 20
+pgsteal_khugepaged 1
+pgsteal_anon 30
+pgsteal_file 91
+oom_kill 2
+";
+
+    #[test]
+    fn scales_kib_and_pages_to_bytes() {
+        let counters = parse(VMSTAT, 4096);
+        assert_eq!(counters.page_in, 2048 * 1024);
+        assert_eq!(counters.page_out, 4096 * 1024);
+        assert_eq!(counters.swap_in, 3 * 4096);
+        assert_eq!(counters.swap_out, 5 * 4096);
+        assert_eq!(counters.major_faults, 42);
+        assert_eq!(counters.oom_kills, 2);
+        // The paging counters do not depend on the page size.
+        assert_eq!(parse(VMSTAT, 65536).page_in, 2048 * 1024);
+        assert_eq!(parse(VMSTAT, 65536).swap_in, 3 * 65536);
+    }
+
+    #[test]
+    fn sums_the_reclaim_once() {
+        // The split by page type counts the same pages again.
+        assert_eq!(parse(VMSTAT, 4096).reclaimed, 121 * 4096);
+    }
+
+    #[test]
+    fn computes_rates() {
+        let previous = parse(VMSTAT, 4096);
+        let current = Counters {
+            page_in: previous.page_in + 4096,
+            major_faults: previous.major_faults + 10,
+            // A counter that went back must not underflow.
+            swap_out: 0,
+            ..previous
+        };
+        let rates = current.rates_since(&previous, 2.0);
+        assert_eq!(rates.page_in, 2048.0);
+        assert_eq!(rates.major_faults, 5.0);
+        assert_eq!(rates.swap_out, 0.0);
+        assert_eq!(rates.reclaimed, 0.0);
+    }
+}